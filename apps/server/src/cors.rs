@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use axum::http::{HeaderName, Method};
+use core_config::CorsConfig;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer, ExposeHeaders};
+
+/// The CORS policy described by `server.cors`; `None` when no origin is
+/// allowed, leaving browsers to the same-origin policy.
+pub fn cors_layer(config: &CorsConfig) -> Result<Option<CorsLayer>, Box<dyn std::error::Error>> {
+    if config.allowed_origins.is_empty() {
+        return Ok(None);
+    }
+
+    let origins = if config.allows_any_origin() && !config.allow_credentials {
+        AllowOrigin::any()
+    } else {
+        let config = config.clone();
+        AllowOrigin::predicate(move |origin, _| {
+            origin.to_str().is_ok_and(|origin| config.allows_origin(origin))
+        })
+    };
+
+    let methods = if is_wildcard(&config.allowed_methods) {
+        AllowMethods::any()
+    } else {
+        AllowMethods::list(
+            config
+                .allowed_methods
+                .iter()
+                .map(|method| {
+                    Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                        .map_err(|_| format!("server.cors.allowed_methods: invalid method '{}'", method))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    };
+
+    let headers = if is_wildcard(&config.allowed_headers) {
+        AllowHeaders::any()
+    } else {
+        AllowHeaders::list(header_names("server.cors.allowed_headers", &config.allowed_headers)?)
+    };
+
+    let exposed = if is_wildcard(&config.exposed_headers) {
+        ExposeHeaders::any()
+    } else {
+        ExposeHeaders::list(header_names("server.cors.exposed_headers", &config.exposed_headers)?)
+    };
+
+    Ok(Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .expose_headers(exposed)
+            .allow_credentials(config.allow_credentials)
+            .max_age(Duration::from_secs(config.max_age_secs)),
+    ))
+}
+
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|value| value == "*")
+}
+
+fn header_names(key: &str, names: &[String]) -> Result<Vec<HeaderName>, String> {
+    names
+        .iter()
+        .map(|name| {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("{}: invalid header name '{}'", key, name))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    fn app(config: &CorsConfig) -> Router {
+        Router::new()
+            .route("/api/users", get(|| async { "ok" }))
+            .layer(cors_layer(config).unwrap().unwrap())
+    }
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method("OPTIONS")
+            .uri("/api/users")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_only_listed_origins_are_allowed() {
+        let config = CorsConfig {
+            allowed_origins: vec![
+                "https://app.example.com".to_string(),
+                "https://*.example.org".to_string(),
+            ],
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+
+        for origin in ["https://app.example.com", "https://admin.eu.example.org"] {
+            let response = app(&config).oneshot(preflight(origin)).await.unwrap();
+            let headers = response.headers();
+            assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], origin);
+            assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+            assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+        }
+
+        for origin in ["https://evil.com", "http://app.example.com", "https://example.org.evil.com"] {
+            let response = app(&config).oneshot(preflight(origin)).await.unwrap();
+            assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        }
+    }
+
+    #[test]
+    fn test_no_origins_means_no_layer() {
+        assert!(cors_layer(&CorsConfig::default()).unwrap().is_none());
+
+        let config = CorsConfig {
+            allowed_origins: vec!["*".to_string()],
+            allowed_headers: vec!["not a header".to_string()],
+            ..CorsConfig::default()
+        };
+        assert!(cors_layer(&config).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use core_db::HealthRegistry;
+
+/// `/health/live` answers as long as the process serves requests;
+/// `/health/ready` checks every registered backend and returns 503 if any is
+/// down. `/health` is kept as an alias of the liveness probe, with the same
+/// body it always had.
+pub fn health_router(registry: Arc<HealthRegistry>) -> Router {
+    Router::new()
+        .route("/health", get(live))
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .with_state(registry)
+}
+
+async fn live() -> impl IntoResponse {
+    Json(serde_json::json!({
+        "status": "healthy",
+        "service": "user-service",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+async fn ready(State(registry): State<Arc<HealthRegistry>>) -> impl IntoResponse {
+    let report = registry.check().await;
+
+    let (status, label) = if report.is_healthy() {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+
+    let body = Json(serde_json::json!({
+        "status": label,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "components": report.components,
+    }));
+
+    (status, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use axum::body::Body;
+    use axum::http::Request;
+    use core_db::DatabaseService;
+    use pkg::{RepositoryError, RepositoryResult};
+    use tower::ServiceExt;
+
+    struct Backend(bool);
+
+    #[async_trait]
+    impl DatabaseService for Backend {
+        async fn health_check(&self) -> RepositoryResult<bool> {
+            if self.0 {
+                Ok(true)
+            } else {
+                Err(RepositoryError::DatabaseError("connection refused".to_string()))
+            }
+        }
+
+        fn connection_info(&self) -> String {
+            "test".to_string()
+        }
+    }
+
+    async fn get_json(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_ready_reports_each_component() {
+        let registry = HealthRegistry::new()
+            .with("postgres", Arc::new(Backend(true)))
+            .with("mongo", Arc::new(Backend(false)));
+        let router = health_router(Arc::new(registry));
+
+        let (status, body) = get_json(router.clone(), "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(body["components"][0]["name"], "postgres");
+        assert_eq!(body["components"][0]["status"], "up");
+        assert_eq!(body["components"][1]["status"], "down");
+        assert!(body["components"][1]["latency_ms"].is_u64());
+
+        let (status, body) = get_json(router, "/health/live").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "healthy");
+    }
+}
@@ -1,14 +1,32 @@
+mod cors;
+mod health;
+
 use std::sync::Arc;
 use std::env;
 
 
-use pkg::{init_logging, RepositoryError};
-use core_config::AppConfig;
-use core_db::DatabaseFactory;
+use pkg::init_logging;
+use core_auth::{Authenticator, JwtVerifier, Principal, RateLimiter, TokenIssuer};
+use core_config::{AppConfig, ConfigError, ConfigIssue};
+use core_db::{DatabaseFactory, DatabasePools, HealthRegistry, Migration};
 use users_module::{
-    delivery::http::{create_user_router, dto::{CreateUserDto, UpdateUserDto}},
-    repositories::InMemoryUserRepository,
-    service::UserService,
+    ApiKeyScope,
+    UserError,
+    delivery::http::{
+        create_api_key_router,
+        create_auth_router,
+        create_password_reset_router,
+        create_user_router,
+        dto::{CreateUserDto, UpdateUserDto},
+    },
+    notification::notifier_from_config,
+    repositories::{
+        InMemoryPasswordResetRepository,
+        InMemorySessionRepository,
+        InMemoryUserRepository,
+        PostgresApiKeyRepository,
+    },
+    service::{ApiKeyService, PasswordResetService, SessionService, UserService},
 };
 
 #[tokio::main]
@@ -20,11 +38,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     init_logging();
 
 
-    let config = AppConfig::from_env()?;
+    let args: Vec<String> = env::args().collect();
 
+    if args.get(1).map(String::as_str) == Some("config:check") {
+        let (config, issues) = AppConfig::load_lenient()?;
+        return check_config(&config, issues);
+    }
+
+
+    let config = AppConfig::load()?;
+
+    config.validate()?;
 
-    let args: Vec<String> = env::args().collect();
-    
     if args.len() > 1 {
         match args[1].as_str() {
             "serve" | "server" | "http" => {
@@ -34,14 +59,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 run_cli_demo(config).await
             }
             "migrate" => {
-                run_migrations(config).await
+                if args[2..].iter().any(|arg| arg == "--dry-run") {
+                    show_migration_plan(config).await
+                } else {
+                    run_migrations(config).await
+                }
+            }
+            "migrate:sql" | "migration:sql" => {
+                print_migration_sql(config).await
             }
             "migrate:status" | "migration:status" => {
-                show_migration_status(config).await
+                show_migration_status(config, &args[2..]).await
+            }
+            "migrate:baseline" | "migration:baseline" => {
+                baseline_migrations(config, &args[2..]).await
             }
             "migrate:list" | "migration:list" => {
                 list_migrations().await
             }
+            "apikey:create" => {
+                create_api_key(config, &args[2..]).await
+            }
+            "apikey:list" => {
+                list_api_keys(config).await
+            }
+            "apikey:revoke" => {
+                revoke_api_key(config, &args[2..]).await
+            }
             _ => {
                 println!("Unknown command: {}", args[1]);
                 print_usage();
@@ -60,15 +104,63 @@ fn print_usage() {
     println!("Commands:");
     println!("  serve, server, http      - Start HTTP API server (default)");
     println!("  cli, demo                - Run CLI demo");
-    println!("  migrate                  - Run database migrations");
-    println!("  migrate:status           - Show migration status");
+    println!("  migrate                  - Run database migrations (--dry-run to only show the plan)");
+    println!("  migrate:sql              - Print the SQL script for pending migrations");
+    println!("  migrate:status           - Show migration status (--format table|json)");
+    println!("  migrate:baseline M V     - Mark module M migrations up to version V as applied");
     println!("  migrate:list             - List all available migrations");
+    println!("  apikey:create NAME       - Create an API key in the database (--scope read-only|admin, default read-only)");
+    println!("  apikey:list              - List API keys in the database");
+    println!("  apikey:revoke ID         - Revoke an API key in the database");
+    println!("  config:check             - Print the effective configuration and validate it");
+    println!();
+    println!("Configuration:");
+    println!("  config/default.toml, config/$APP_ENV.toml and config/local.toml are merged,");
+    println!("  then overridden by APP__SECTION__KEY env vars (e.g. APP__SERVER__PORT=8080).");
     println!();
     println!("Environment Variables:");
+    println!("  APP_ENV              - Configuration profile (default: development)");
+    println!("  APP_CONFIG_DIR       - Configuration directory (default: config)");
     println!("  DATABASE_URL         - PostgreSQL connection string");
     println!("  SERVER_HOST          - Server host (default: 0.0.0.0)");
     println!("  SERVER_PORT          - Server port (default: 3000)");
     println!("  USE_POSTGRES         - Use PostgreSQL instead of in-memory (true/false)");
+    println!("  JWT_SECRET           - HS256 secret for bearer tokens (auth.jwt_secret)");
+    println!("  SESSION_TIMEOUT      - Login session lifetime in hours (default: 24)");
+}
+
+fn check_config(
+    config: &AppConfig,
+    mut issues: Vec<ConfigIssue>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("⚙️  Effective configuration (profile: {})\n", config.environment);
+
+    let entries = config.entries();
+    let key_width = entries.iter().map(|e| e.key.len()).max().unwrap_or(0);
+    let value_width = entries.iter().map(|e| e.value.len()).max().unwrap_or(0);
+
+    for entry in &entries {
+        println!(
+            "  {:<key_width$} = {:<value_width$}  ({})",
+            entry.key, entry.value, entry.source,
+        );
+    }
+    println!();
+
+    match config.validate() {
+        Ok(()) => {}
+        Err(ConfigError::Invalid(invalid)) => issues.extend(invalid),
+        Err(e) => return Err(e.into()),
+    }
+
+    if issues.is_empty() {
+        println!("✅ Configuration is valid");
+        Ok(())
+    } else {
+        let e = ConfigError::Invalid(issues);
+        eprintln!("❌ {}", e);
+        Err(e.into())
+    }
 }
 
 async fn run_http_server(config: AppConfig) -> Result<(), Box<dyn std::error::Error>> {
@@ -76,21 +168,113 @@ async fn run_http_server(config: AppConfig) -> Result<(), Box<dyn std::error::Er
 
 
     let repository = Arc::new(InMemoryUserRepository::new());
-    let service = Arc::new(UserService::new(repository));
+    let session_repository = Arc::new(InMemorySessionRepository::new());
+    let notifier = notifier_from_config(&config.notifications)?;
+    let password_resets = PasswordResetService::new(
+        repository.clone(),
+        Arc::new(InMemoryPasswordResetRepository::new()),
+        session_repository.clone(),
+        chrono::Duration::minutes(config.auth.password_reset_ttl_minutes as i64),
+    );
+    let (service, password_resets) = match notifier {
+        Some(notifier) => (
+            UserService::new(repository).with_notifier(notifier.clone()),
+            password_resets.with_notifier(notifier),
+        ),
+        None => (UserService::new(repository), password_resets),
+    };
+    let service = Arc::new(service.with_sessions(session_repository.clone()));
+    // The same table the `apikey:*` commands manage.
+    let pools = DatabaseFactory::create_database_pools(&config.database).await?;
+    let api_keys = Arc::new(ApiKeyService::new(Arc::new(PostgresApiKeyRepository::with_pools(
+        pools.clone(),
+    ))));
+
+
+    let (authenticator, sessions) = if config.auth.enabled {
+        let verifier = Arc::new(JwtVerifier::from_config(&config.auth)?);
+        let authenticator = Arc::new(Authenticator::new(verifier.clone()).with_api_keys(api_keys.clone()));
+        let sessions = match TokenIssuer::from_config(&config.auth)? {
+            Some(issuer) => Some(Arc::new(SessionService::new(
+                session_repository,
+                Arc::new(issuer),
+                verifier.clone(),
+                chrono::Duration::hours(config.auth.session_timeout_hours as i64),
+            ))),
+            None => {
+                tracing::warn!("No auth.signing_key_path for RS256, /api/auth/* is disabled");
+                None
+            }
+        };
+        (Some(authenticator), sessions)
+    } else {
+        tracing::warn!("⚠️  Authentication disabled by configuration (auth.enabled), /api/users is public");
+        (None, None)
+    };
 
+    let rate_limiter = RateLimiter::from_config(&config.server.rate_limit).await?.map(Arc::new);
+    if rate_limiter.is_none() {
+        tracing::warn!("Rate limiting disabled by configuration (server.rate_limit.enabled)");
+    }
 
-    let app = create_user_router(service);
+    let app = if config.modules.users_enabled {
+        let users = create_user_router(service.clone(), authenticator.clone(), rate_limiter.clone())
+            .merge(create_password_reset_router(Arc::new(password_resets), rate_limiter.clone()));
+        let users = match &authenticator {
+            Some(authenticator) => users.merge(create_api_key_router(
+                api_keys,
+                authenticator.clone(),
+                rate_limiter.clone(),
+            )),
+            None => users,
+        };
+        match (sessions, authenticator) {
+            (Some(sessions), Some(authenticator)) => {
+                users.merge(create_auth_router(service, sessions, authenticator, rate_limiter))
+            }
+            _ => users,
+        }
+    } else {
+        tracing::warn!("Users module disabled by configuration (modules.users_enabled)");
+        axum::Router::new()
+    };
+    let app = app.merge(health::health_router(Arc::new(health_registry(&config, &pools).await?)));
+    let app = match cors::cors_layer(&config.server.cors)? {
+        Some(cors) => app.layer(cors),
+        None => app,
+    };
+    let app = app.layer(axum::middleware::from_fn(pkg::http::request_id));
 
 
     let addr = format!("{}:{}", config.server.host, config.server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
     tracing::info!("✅ Server running on http://{}", addr);
-    axum::serve(listener, app).await?;
+    // Connection addresses are the client IPs rate limits are keyed by.
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await?;
 
     Ok(())
 }
 
+/// Backends reported by `/health/ready`: the `[database]` pools, which hold the
+/// API keys, and `[mongo]` when configured. With `lazy_connect` the server
+/// starts, and reports not-ready, while a backend is unreachable; otherwise
+/// startup waits for it according to `connect_retry`.
+async fn health_registry(
+    config: &AppConfig,
+    pools: &DatabasePools,
+) -> Result<HealthRegistry, Box<dyn std::error::Error>> {
+    let mut registry = HealthRegistry::new();
+    registry.register("postgres", Arc::new(pools.clone()));
+
+    if let Some(mongo) = &config.mongo {
+        let client = DatabaseFactory::create_mongo_client(mongo).await?;
+        registry.register("mongo", Arc::new(client));
+    }
+
+    Ok(registry)
+}
+
 async fn run_cli_demo(_config: AppConfig) -> Result<(), Box<dyn std::error::Error>> {
     println!("=== Repository Pattern CLI Demo ===\n");
 
@@ -111,22 +295,154 @@ async fn run_migrations(config: AppConfig) -> Result<(), Box<dyn std::error::Err
     
 
 
-    let all_migrations: Vec<_> = vec![
-        users_module::USER_MIGRATIONS,
+    let runner = MigrationRunner::new(pool);
+    runner.run_migrations(&all_migrations()).await?;
+
+    println!("\n✅ Migration process completed successfully!");
 
+    Ok(())
+}
 
+async fn show_migration_plan(config: AppConfig) -> Result<(), Box<dyn std::error::Error>> {
+    use core_db::MigrationRunner;
 
-    ]
-    .into_iter()
-    .flatten()
-    .copied()
-    .collect();
-    
+    println!("🔍 Migration dry run (nothing will be executed)\n");
 
+    let pool = DatabaseFactory::create_postgres_pool(&config.database).await?;
     let runner = MigrationRunner::new(pool);
-    runner.run_migrations(&all_migrations).await?;
+    let plan = runner.plan(&all_migrations()).await?;
 
-    println!("\n✅ Migration process completed successfully!");
+    if plan.is_empty() {
+        println!("✅ All migrations up to date");
+        return Ok(());
+    }
+
+    println!("📦 {} pending migration(s):", plan.pending.len());
+    for migration in &plan.pending {
+        println!("   → {} - {}", migration.id(), migration.name);
+    }
+
+    println!("\n═══════════════════════════════════════════════════════════════\n");
+    println!("{}", plan.to_sql());
+
+    Ok(())
+}
+
+async fn print_migration_sql(config: AppConfig) -> Result<(), Box<dyn std::error::Error>> {
+    use core_db::MigrationRunner;
+
+    let pool = DatabaseFactory::create_postgres_pool(&config.database).await?;
+    let runner = MigrationRunner::new(pool);
+    let plan = runner.plan(&all_migrations()).await?;
+
+    print!("{}", plan.to_sql());
+    if plan.pending.iter().any(Migration::is_code) {
+        eprintln!("⚠️  The script stops at a Rust code migration; run 'migrate' for the rest.");
+    }
+
+    Ok(())
+}
+
+async fn baseline_migrations(
+    config: AppConfig,
+    args: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    use core_db::MigrationRunner;
+
+    let (module, version) = match args {
+        [module, version, ..] => (module.as_str(), version.parse::<i32>()?),
+        _ => return Err("Usage: server migrate:baseline <module> <version>".into()),
+    };
+
+    println!("📌 Baselining {} up to version {}...\n", module, version);
+
+    let pool = DatabaseFactory::create_postgres_pool(&config.database).await?;
+    let runner = MigrationRunner::new(pool);
+    let baselined = runner.baseline(&all_migrations(), module, version).await?;
+
+    if baselined.is_empty() {
+        println!("✅ Nothing to baseline, all migrations up to v{} are already recorded", version);
+    } else {
+        for migration in &baselined {
+            println!("   ⊙ {} - {}", migration.id(), migration.name);
+        }
+        println!("\n✅ Marked {} migration(s) as applied without running them", baselined.len());
+    }
+
+    Ok(())
+}
+
+async fn api_key_service(
+    config: &AppConfig,
+) -> Result<ApiKeyService<PostgresApiKeyRepository>, Box<dyn std::error::Error>> {
+    let pools = DatabaseFactory::create_database_pools(&config.database).await?;
+    Ok(ApiKeyService::new(Arc::new(PostgresApiKeyRepository::with_pools(pools))))
+}
+
+async fn create_api_key(config: AppConfig, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let name = args
+        .first()
+        .filter(|arg| !arg.starts_with("--"))
+        .ok_or("Usage: server apikey:create <name> [--scope read-only|admin]")?;
+    let scope: ApiKeyScope = option_value(args, "--scope").unwrap_or("read-only").parse()?;
+
+    let service = api_key_service(&config).await?;
+    let (api_key, key) = service.create(&Principal::system(), name, scope).await?;
+
+    println!("🔑 Created {} API key '{}' (ID: {})
+", scope.as_str(), api_key.name, api_key.id);
+    println!("   {}
+", key);
+    println!("⚠️  Store it now, it cannot be shown again. Send it as the X-Api-Key header.");
+
+    Ok(())
+}
+
+async fn list_api_keys(config: AppConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let service = api_key_service(&config).await?;
+    let keys = service.list(&Principal::system()).await?;
+
+    if keys.is_empty() {
+        println!("No API keys. Create one with 'server apikey:create <name>'.");
+        return Ok(());
+    }
+
+    let name_width = keys.iter().map(|k| k.name.len()).max().unwrap_or(4).max(4);
+    println!(
+        "{:<36}  {:<name_width$}  {:<11}  {:<9}  {:<19}  {:<19}",
+        "ID", "NAME", "PREFIX", "SCOPE", "LAST USED", "REVOKED",
+    );
+    println!("{}", "─".repeat(name_width + 106));
+
+    let timestamp = |t: Option<chrono::DateTime<chrono::Utc>>| {
+        t.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    for key in &keys {
+        println!(
+            "{:<36}  {:<name_width$}  {:<11}  {:<9}  {:<19}  {:<19}",
+            key.id,
+            key.name,
+            key.prefix,
+            key.scope.as_str(),
+            timestamp(key.last_used_at),
+            timestamp(key.revoked_at),
+        );
+    }
+
+    Ok(())
+}
+
+async fn revoke_api_key(config: AppConfig, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let id = args.first().ok_or("Usage: server apikey:revoke <id>")?;
+    let id = uuid::Uuid::parse_str(id)?;
+
+    let service = api_key_service(&config).await?;
+    if service.revoke(&Principal::system(), id).await? {
+        println!("✅ Revoked API key {}", id);
+    } else {
+        println!("✅ API key {} was already revoked", id);
+    }
 
     Ok(())
 }
@@ -134,6 +450,7 @@ async fn run_migrations(config: AppConfig) -> Result<(), Box<dyn std::error::Err
 async fn run_examples<R: users_module::repositories::UserRepository + Send + Sync>(
     service: Arc<UserService<R>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let caller = Principal::system();
 
     println!("1. Creating users...");
     let user1_dto = CreateUserDto {
@@ -141,9 +458,10 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
         email: "john@example.com".to_string(),
         full_name: "John Doe".to_string(),
         age: Some(30),
+        password: "Demo-password1".to_string(),
     };
 
-    let user1 = service.create_user(user1_dto).await?;
+    let user1 = service.create_user(&caller, user1_dto).await?;
     println!("   Created user: {} (ID: {})", user1.username, user1.id);
 
     let user2_dto = CreateUserDto {
@@ -151,9 +469,10 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
         email: "jane@example.com".to_string(),
         full_name: "Jane Smith".to_string(),
         age: Some(25),
+        password: "Demo-password1".to_string(),
     };
 
-    let user2 = service.create_user(user2_dto).await?;
+    let user2 = service.create_user(&caller, user2_dto).await?;
     println!("   Created user: {} (ID: {})", user2.username, user2.id);
 
     let user3_dto = CreateUserDto {
@@ -161,9 +480,10 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
         email: "bob@example.com".to_string(),
         full_name: "Bob Wilson".to_string(),
         age: Some(35),
+        password: "Demo-password1".to_string(),
     };
 
-    let user3 = service.create_user(user3_dto).await?;
+    let user3 = service.create_user(&caller, user3_dto).await?;
     println!("   Created user: {} (ID: {})\n", user3.username, user3.id);
 
 
@@ -173,30 +493,31 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
         email: "different@example.com".to_string(),
         full_name: "Different User".to_string(),
         age: Some(40),
+        password: "Demo-password1".to_string(),
     };
 
-    match service.create_user(duplicate_dto).await {
+    match service.create_user(&caller, duplicate_dto).await {
         Ok(_) => println!("   Unexpected success!"),
-        Err(RepositoryError::ValidationError(msg)) => {
-            println!("   Expected error: {}\n", msg)
+        Err(e @ (UserError::UsernameTaken(_) | UserError::EmailTaken(_))) => {
+            println!("   Expected error: {}\n", e)
         }
         Err(e) => println!("   Unexpected error: {}\n", e),
     }
 
 
     println!("3. Getting user by ID...");
-    let fetched_user = service.get_user(user1.id).await?;
+    let fetched_user = service.get_user(&caller, user1.id).await?;
     println!("   Found: {} - {}\n", fetched_user.full_name, fetched_user.email);
 
 
     println!("4. Finding user by username...");
-    if let Some(user) = service.find_by_username("jane_smith").await? {
+    if let Some(user) = service.find_by_username(&caller, "jane_smith").await? {
         println!("   Found: {} ({})\n", user.full_name, user.email);
     }
 
 
     println!("5. Getting all users...");
-    let all_users = service.get_all_users().await?;
+    let all_users = service.get_all_users(&caller).await?;
     println!("   Total users: {}", all_users.len());
     for user in &all_users {
         println!(
@@ -215,7 +536,7 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
         age: Some(31),
     };
 
-    let updated_user = service.update_user(user1.id, update_dto).await?;
+    let updated_user = service.update_user(&caller, user1.id, update_dto).await?;
     println!(
         "   Updated: {} - {}\n",
         updated_user.full_name, updated_user.email
@@ -223,7 +544,7 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
 
 
     println!("7. Finding users by age range (25-32)...");
-    let users_in_range = service.get_users_by_age_range(25, 32).await?;
+    let users_in_range = service.get_users_by_age_range(&caller, 25, 32).await?;
     println!("   Found {} users:", users_in_range.len());
     for user in users_in_range {
         println!(
@@ -236,7 +557,7 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
 
 
     println!("8. Getting user statistics...");
-    let stats = service.get_statistics().await?;
+    let stats = service.get_statistics(&caller).await?;
     println!("   Total Users: {}", stats.total_users);
     println!("   Users with Age: {}", stats.users_with_age);
     if let Some(avg) = stats.average_age {
@@ -247,19 +568,19 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
 
 
     println!("9. Deleting user...");
-    let deleted = service.delete_user(user3.id).await?;
+    let deleted = service.delete_user(&caller, user3.id).await?;
     if deleted {
         println!("   User {} deleted successfully", user3.username);
     }
 
-    let all_remaining = service.get_all_users().await?;
+    let all_remaining = service.get_all_users(&caller).await?;
     println!("   Remaining users: {}\n", all_remaining.len());
 
 
     println!("10. Trying to get deleted user...");
-    match service.get_user(user3.id).await {
+    match service.get_user(&caller, user3.id).await {
         Ok(_) => println!("   Unexpected success!"),
-        Err(RepositoryError::NotFound(id)) => {
+        Err(UserError::UserNotFound(id)) => {
             println!("   Expected error: User with ID {} not found\n", id)
         }
         Err(e) => println!("   Unexpected error: {}\n", e),
@@ -271,67 +592,99 @@ async fn run_examples<R: users_module::repositories::UserRepository + Send + Syn
 }
 
 
-async fn show_migration_status(config: AppConfig) -> Result<(), Box<dyn std::error::Error>> {
-    use core_db::MigrationRunner;
+async fn show_migration_status(
+    config: AppConfig,
+    args: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    use core_db::{MigrationRunner, MigrationState};
 
-    println!("📊 Migration Status Report\n");
-    println!("═══════════════════════════════════════════════════════════════\n");
+    let format = option_value(args, "--format").unwrap_or("table");
+    if format != "table" && format != "json" {
+        return Err(format!("Unknown format '{}', expected 'table' or 'json'", format).into());
+    }
 
     let pool = DatabaseFactory::create_postgres_pool(&config.database).await?;
     let runner = MigrationRunner::new(pool);
+    let reports = runner.status(&all_migrations()).await?;
 
-    match runner.get_status().await {
-        Ok(statuses) => {
-            if statuses.is_empty() {
-                println!("❌ No migrations have been applied yet.");
-                println!("\n💡 Run 'cargo run -p server migrate' to apply migrations.");
-            } else {
-                println!("✅ Found {} applied migration(s):\n", statuses.len());
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
 
+    println!("📊 Migration Status Report\n");
 
-                let mut by_module: std::collections::HashMap<String, Vec<_>> = std::collections::HashMap::new();
-                for status in statuses {
-                    by_module.entry(status.module.clone()).or_insert_with(Vec::new).push(status);
-                }
+    if reports.is_empty() {
+        println!("❌ No migrations defined or applied.");
+        return Ok(());
+    }
 
-                for (module, migrations) in by_module.iter() {
-                    println!("📦 Module: {}", module);
-                    println!("   ├─ {} migration(s) applied", migrations.len());
-                    
-                    for migration in migrations {
-                        println!("   │");
-                        println!("   ├─ Version: {}", migration.version);
-                        println!("   │  Name: {}", migration.name);
-                        println!("   │  Applied: {}", migration.applied_at);
-                        println!("   │  Execution: {}ms", migration.execution_time_ms);
-                    }
-                    println!();
-                }
+    let name_width = reports.iter().map(|r| r.name.len()).max().unwrap_or(4).max(4);
+    let module_width = reports.iter().map(|r| r.module.len()).max().unwrap_or(6).max(6);
 
-                println!("═══════════════════════════════════════════════════════════════");
-            }
-        }
-        Err(e) => {
-            eprintln!("❌ Error fetching migration status: {}", e);
-            return Err(Box::new(e));
-        }
+    println!(
+        "{:<module_width$}  {:>7}  {:<name_width$}  {:<17}  {:<9}  {:<19}",
+        "MODULE", "VERSION", "NAME", "STATE", "BASELINED", "APPLIED AT",
+    );
+    println!("{}", "─".repeat(module_width + name_width + 64));
+
+    for report in &reports {
+        println!(
+            "{:<module_width$}  {:>7}  {:<name_width$}  {:<17}  {:<9}  {:<19}",
+            report.module,
+            report.version,
+            report.name,
+            report.state.as_str(),
+            if report.baselined { "yes" } else { "-" },
+            report.applied_at.as_deref().unwrap_or("-"),
+        );
+    }
+
+    let count = |state: MigrationState| reports.iter().filter(|r| r.state == state).count();
+    println!();
+    println!(
+        "Applied: {}  Pending: {}  Checksum mismatch: {}  Missing in code: {}",
+        count(MigrationState::Applied),
+        count(MigrationState::Pending),
+        count(MigrationState::ChecksumMismatch),
+        count(MigrationState::MissingInCode),
+    );
+
+    if count(MigrationState::Pending) > 0 {
+        println!("\n💡 Run 'cargo run -p server migrate' to apply pending migrations.");
     }
 
     Ok(())
 }
 
+/// Value of a `--name value` or `--name=value` command-line option.
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().enumerate().find_map(|(index, arg)| {
+        if arg == name {
+            args.get(index + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix(name).and_then(|rest| rest.strip_prefix('='))
+        }
+    })
+}
 
-async fn list_migrations() -> Result<(), Box<dyn std::error::Error>> {
-    println!("📋 Available Migrations\n");
-    println!("═══════════════════════════════════════════════════════════════\n");
-
-    let all_migrations: Vec<_> = vec![
+fn all_migrations() -> Vec<Migration> {
+    vec![
         users_module::USER_MIGRATIONS,
 
     ]
     .into_iter()
     .flatten()
-    .collect();
+    .cloned()
+    .collect()
+}
+
+
+async fn list_migrations() -> Result<(), Box<dyn std::error::Error>> {
+    println!("📋 Available Migrations\n");
+    println!("═══════════════════════════════════════════════════════════════\n");
+
+    let all_migrations = all_migrations();
 
     if all_migrations.is_empty() {
         println!("❌ No migrations found.");
@@ -341,7 +694,7 @@ async fn list_migrations() -> Result<(), Box<dyn std::error::Error>> {
 
         let mut by_module: std::collections::HashMap<&str, Vec<_>> = std::collections::HashMap::new();
         for migration in &all_migrations {
-            by_module.entry(migration.module).or_insert_with(Vec::new).push(*migration);
+            by_module.entry(migration.module.as_ref()).or_default().push(migration);
         }
 
         for (module, migrations) in by_module.iter() {
@@ -354,7 +707,11 @@ async fn list_migrations() -> Result<(), Box<dyn std::error::Error>> {
                 println!("   │  Name: {}", migration.name);
                 println!("   │  ID: {}", migration.id());
                 println!("   │  Checksum: {}", migration.checksum());
-                let sql_preview = migration.sql.lines().next().unwrap_or("").trim();
+                let sql_preview = if migration.is_code() {
+                    "(Rust code migration)"
+                } else {
+                    migration.sql.lines().next().unwrap_or("").trim()
+                };
                 println!("   │  SQL Preview: {}...", 
                     if sql_preview.len() > 60 { 
                         &sql_preview[..60] 
@@ -39,6 +39,12 @@ where
         }
     }
 
+    pub fn with_entries(entries: impl IntoIterator<Item = (ID, T)>) -> Self {
+        Self {
+            storage: Arc::new(RwLock::new(entries.into_iter().collect())),
+        }
+    }
+
     pub async fn insert(&self, id: ID, entity: T) -> RepositoryResult<()> {
         let mut storage = self.storage.write().await;
         if storage.contains_key(&id) {
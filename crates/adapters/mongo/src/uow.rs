@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use mongodb::{Client, ClientSession};
-use core_db::UnitOfWork;
+use core_config::MongoConfig;
+use core_db::{DatabaseFactory, UnitOfWork};
 use pkg::{RepositoryError, RepositoryResult};
 
 pub struct MongoUnitOfWork {
@@ -16,6 +17,16 @@ impl MongoUnitOfWork {
         }
     }
 
+    /// Build the client from configuration via [`DatabaseFactory::create_mongo_client`].
+    pub async fn connect(config: &MongoConfig) -> RepositoryResult<Self> {
+        let client = DatabaseFactory::create_mongo_client(config).await?;
+        Ok(Self::new(client))
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
     pub fn session(&mut self) -> Option<&mut ClientSession> {
         self.session.as_mut()
     }
@@ -34,7 +45,7 @@ impl UnitOfWork for MongoUnitOfWork {
             .client
             .start_session(None)
             .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+            .map_err(RepositoryError::from)?;
 
         self.session = Some(session);
 
@@ -43,7 +54,7 @@ impl UnitOfWork for MongoUnitOfWork {
             session
                 .start_transaction(None)
                 .await
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                .map_err(RepositoryError::from)?;
         }
 
         Ok(())
@@ -54,7 +65,7 @@ impl UnitOfWork for MongoUnitOfWork {
             session
                 .commit_transaction()
                 .await
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                .map_err(RepositoryError::from)?;
             Ok(())
         } else {
             Err(RepositoryError::InternalError(
@@ -68,7 +79,7 @@ impl UnitOfWork for MongoUnitOfWork {
             session
                 .abort_transaction()
                 .await
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                .map_err(RepositoryError::from)?;
             Ok(())
         } else {
             Err(RepositoryError::InternalError(
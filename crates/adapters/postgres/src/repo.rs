@@ -1,15 +1,20 @@
-use async_trait::async_trait;
-use sqlx::{PgPool, Postgres, FromRow};
+use sqlx::{Encode, PgPool, Postgres, FromRow, Type};
+use core_db::DatabasePools;
 use pkg::{RepositoryError, RepositoryResult};
 
 
-
+/// Generic Postgres access for one table.
+///
+/// The named reads (`find_*`, `count`) run on a read replica when
+/// [`DatabasePools`] has any, falling back to the primary. The `query_*` and
+/// `execute*` helpers take arbitrary SQL, which may write (`INSERT ...
+/// RETURNING`), so they always run on the primary.
 #[derive(Debug, Clone)]
 pub struct PostgresBaseRepository<T>
 where
     T: for<'r> FromRow<'r, sqlx::postgres::PgRow> + Send + Sync + Unpin,
 {
-    pool: PgPool,
+    pools: DatabasePools,
     table_name: String,
     _phantom: std::marker::PhantomData<T>,
 }
@@ -19,21 +24,94 @@ where
     T: for<'r> FromRow<'r, sqlx::postgres::PgRow> + Send + Sync + Unpin,
 {
     pub fn new(pool: PgPool, table_name: impl Into<String>) -> Self {
+        Self::with_pools(DatabasePools::single(pool), table_name)
+    }
+
+    pub fn with_pools(pools: DatabasePools, table_name: impl Into<String>) -> Self {
         Self {
-            pool,
+            pools,
             table_name: table_name.into(),
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// The primary pool, for writes and transactions.
     pub fn pool(&self) -> &PgPool {
-        &self.pool
+        self.pools.primary()
+    }
+
+    pub fn pools(&self) -> &DatabasePools {
+        &self.pools
     }
 
     pub fn table_name(&self) -> &str {
         &self.table_name
     }
 
+    pub async fn find_by_id<ID>(&self, id: ID) -> RepositoryResult<Option<T>>
+    where
+        ID: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + Sync,
+    {
+        self.find_one_by_column("id", id).await
+    }
+
+    pub async fn find_all(&self) -> RepositoryResult<Vec<T>> {
+        let sql = format!("SELECT * FROM {}", self.table_name);
+        self.pools
+            .read(|pool| {
+                let sql = sql.as_str();
+                async move { sqlx::query_as::<_, T>(sql).fetch_all(&pool).await }
+            })
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    /// First row whose `column` equals `value`. `column` must be a trusted
+    /// identifier, it is not escaped.
+    pub async fn find_one_by_column<V>(&self, column: &str, value: V) -> RepositoryResult<Option<T>>
+    where
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + Sync,
+    {
+        let sql = format!("SELECT * FROM {} WHERE {} = $1", self.table_name, column);
+        self.pools
+            .read(|pool| {
+                let value = value.clone();
+                let sql = sql.as_str();
+                async move { sqlx::query_as::<_, T>(sql).bind(value).fetch_optional(&pool).await }
+            })
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    /// Every row whose `column` equals `value`. `column` must be a trusted
+    /// identifier, it is not escaped.
+    pub async fn find_all_by_column<V>(&self, column: &str, value: V) -> RepositoryResult<Vec<T>>
+    where
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + Sync,
+    {
+        let sql = format!("SELECT * FROM {} WHERE {} = $1", self.table_name, column);
+        self.pools
+            .read(|pool| {
+                let value = value.clone();
+                let sql = sql.as_str();
+                async move { sqlx::query_as::<_, T>(sql).bind(value).fetch_all(&pool).await }
+            })
+            .await
+            .map_err(RepositoryError::from)
+    }
+
+    pub async fn count(&self) -> RepositoryResult<usize> {
+        let sql = format!("SELECT COUNT(*) FROM {}", self.table_name);
+        self.pools
+            .read(|pool| {
+                let sql = sql.as_str();
+                async move { sqlx::query_scalar::<_, i64>(sql).fetch_one(&pool).await }
+            })
+            .await
+            .map(|count| count as usize)
+            .map_err(RepositoryError::from)
+    }
+
     
     pub async fn query_one<'q, Q>(
         &self,
@@ -42,10 +120,7 @@ where
     where
         Q: sqlx::Execute<'q, Postgres>,
     {
-        sqlx::query_as::<_, T>(query.sql())
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+        self.query_one_raw(query.sql()).await
     }
 
     
@@ -56,10 +131,7 @@ where
     where
         Q: sqlx::Execute<'q, Postgres>,
     {
-        sqlx::query_as::<_, T>(query.sql())
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+        self.query_all_raw(query.sql()).await
     }
 
     
@@ -71,34 +143,34 @@ where
         Q: sqlx::Execute<'q, Postgres>,
     {
         sqlx::query(query.sql())
-            .execute(&self.pool)
+            .execute(self.pools.primary())
             .await
             .map(|result| result.rows_affected())
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+            .map_err(RepositoryError::from)
     }
 
     
     pub async fn query_one_raw(&self, sql: &str) -> RepositoryResult<Option<T>> {
         sqlx::query_as::<_, T>(sql)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.pools.primary())
             .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+            .map_err(RepositoryError::from)
     }
 
     
     pub async fn query_all_raw(&self, sql: &str) -> RepositoryResult<Vec<T>> {
         sqlx::query_as::<_, T>(sql)
-            .fetch_all(&self.pool)
+            .fetch_all(self.pools.primary())
             .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+            .map_err(RepositoryError::from)
     }
 
     
     pub async fn execute_raw(&self, sql: &str) -> RepositoryResult<u64> {
         sqlx::query(sql)
-            .execute(&self.pool)
+            .execute(self.pools.primary())
             .await
             .map(|result| result.rows_affected())
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+            .map_err(RepositoryError::from)
     }
 }
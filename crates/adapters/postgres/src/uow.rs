@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use sqlx::{PgPool, Transaction, Postgres};
-use core_db::UnitOfWork;
+use core_db::{DatabasePools, UnitOfWork};
 use pkg::{RepositoryError, RepositoryResult};
 
 
@@ -17,6 +17,11 @@ impl PostgresUnitOfWork {
         }
     }
 
+    /// Transactions always run on the primary, never on a read replica.
+    pub fn from_pools(pools: &DatabasePools) -> Self {
+        Self::new(pools.primary().clone())
+    }
+
     pub fn transaction(&mut self) -> Option<&mut Transaction<'static, Postgres>> {
         self.transaction.as_mut()
     }
@@ -35,7 +40,7 @@ impl UnitOfWork for PostgresUnitOfWork {
             .pool
             .begin()
             .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+            .map_err(RepositoryError::from)?;
 
         let tx_static: Transaction<'static, Postgres> = unsafe {
             std::mem::transmute(tx)
@@ -49,7 +54,7 @@ impl UnitOfWork for PostgresUnitOfWork {
         if let Some(tx) = self.transaction.take() {
             tx.commit()
                 .await
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                .map_err(RepositoryError::from)?;
             Ok(())
         } else {
             Err(RepositoryError::InternalError(
@@ -62,7 +67,7 @@ impl UnitOfWork for PostgresUnitOfWork {
         if let Some(tx) = self.transaction.take() {
             tx.rollback()
                 .await
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                .map_err(RepositoryError::from)?;
             Ok(())
         } else {
             Err(RepositoryError::InternalError(
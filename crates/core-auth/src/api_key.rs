@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use axum::{async_trait, http::HeaderMap};
+
+use crate::error::{AuthError, AuthResult};
+use crate::jwt::JwtVerifier;
+use crate::middleware::bearer_token;
+use crate::principal::Principal;
+
+/// Header carrying an API key on service-to-service calls.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Looks up API keys presented in [`API_KEY_HEADER`]. Implemented by the
+/// module that stores them.
+#[async_trait]
+pub trait ApiKeyVerifier: Send + Sync {
+    /// Principal of the service holding `key`; an error if the key is unknown
+    /// or revoked.
+    async fn verify_api_key(&self, key: &str) -> AuthResult<Principal>;
+}
+
+/// Credentials accepted by [`require_auth`](crate::require_auth): bearer
+/// tokens always, and API keys once a verifier is attached.
+#[derive(Clone)]
+pub struct Authenticator {
+    jwt: Arc<JwtVerifier>,
+    api_keys: Option<Arc<dyn ApiKeyVerifier>>,
+}
+
+impl Authenticator {
+    pub fn new(jwt: Arc<JwtVerifier>) -> Self {
+        Self { jwt, api_keys: None }
+    }
+
+    pub fn with_api_keys(mut self, api_keys: Arc<dyn ApiKeyVerifier>) -> Self {
+        self.api_keys = Some(api_keys);
+        self
+    }
+
+    /// An `X-Api-Key` header takes precedence over `Authorization`, so a
+    /// request is never authenticated by a second credential after the first
+    /// was rejected.
+    pub async fn authenticate(&self, headers: &HeaderMap) -> AuthResult<Principal> {
+        let Some(value) = headers.get(API_KEY_HEADER) else {
+            return bearer_token(headers).and_then(|token| self.jwt.verify(token));
+        };
+
+        let api_keys = self
+            .api_keys
+            .as_ref()
+            .ok_or_else(|| AuthError::InvalidToken("API keys are not accepted".to_string()))?;
+        let key = value
+            .to_str()
+            .map_err(|_| AuthError::InvalidToken("malformed X-Api-Key header".to_string()))?;
+        api_keys.verify_api_key(key.trim()).await
+    }
+}
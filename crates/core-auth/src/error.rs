@@ -0,0 +1,73 @@
+use axum::{
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use pkg::{ErrorCode, ProblemDetails};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Missing bearer token")]
+    MissingToken,
+
+    #[error("Invalid token: {0}")]
+    InvalidToken(String),
+
+    #[error("Token has expired")]
+    Expired,
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Authentication is misconfigured: {0}")]
+    Configuration(String),
+}
+
+pub type AuthResult<T> = Result<T, AuthError>;
+
+impl From<jsonwebtoken::errors::Error> for AuthError {
+    fn from(error: jsonwebtoken::errors::Error) -> Self {
+        match error.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::Expired,
+            _ => AuthError::InvalidToken(error.to_string()),
+        }
+    }
+}
+
+impl AuthError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AuthError::MissingToken | AuthError::InvalidToken(_) | AuthError::Expired => {
+                ErrorCode::Unauthorized
+            }
+            AuthError::Forbidden(_) => ErrorCode::Forbidden,
+            AuthError::Configuration(_) => ErrorCode::InternalError,
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let code = self.code();
+        let detail = match &self {
+            AuthError::Configuration(msg) => {
+                tracing::error!("Authentication is misconfigured: {}", msg);
+                pkg::INTERNAL_ERROR_DETAIL.to_string()
+            }
+            // Don't tell clients why a token was rejected beyond expiry.
+            AuthError::InvalidToken(msg) => {
+                tracing::debug!("Rejected bearer token: {}", msg);
+                "Invalid bearer token".to_string()
+            }
+            other => other.to_string(),
+        };
+
+        let mut response = ProblemDetails::new(code).with_detail(detail).into_response();
+        if code == ErrorCode::Unauthorized {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        response
+    }
+}
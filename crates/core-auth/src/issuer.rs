@@ -0,0 +1,169 @@
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use core_config::{AuthConfig, JwtAlgorithm};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{AuthError, AuthResult};
+use crate::principal::{Claims, ACCESS_TOKEN_USE, REFRESH_TOKEN_USE};
+
+/// Tokens returned by a successful login.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Always `Bearer`.
+    pub token_type: String,
+    /// Lifetime of the access token in seconds.
+    pub expires_in: u64,
+    /// `jti` of the refresh token.
+    #[serde(skip)]
+    pub refresh_token_id: String,
+}
+
+/// Signs access and refresh tokens that [`JwtVerifier`](crate::JwtVerifier)
+/// built from the same [`AuthConfig`] accepts.
+#[derive(Clone)]
+pub struct TokenIssuer {
+    header: Header,
+    key: EncodingKey,
+    issuer: Option<String>,
+    audience: Option<String>,
+    access_ttl_secs: u64,
+    refresh_ttl_secs: u64,
+}
+
+impl TokenIssuer {
+    /// `None` for `RS256` without `auth.signing_key_path`, where tokens come
+    /// from an external identity provider and this service only verifies them.
+    pub fn from_config(config: &AuthConfig) -> AuthResult<Option<Self>> {
+        let (header, key) = match config.algorithm {
+            JwtAlgorithm::Hs256 => {
+                let secret = config.jwt_secret.as_deref().ok_or_else(|| {
+                    AuthError::Configuration("auth.jwt_secret is required for HS256".to_string())
+                })?;
+                (
+                    Header::new(Algorithm::HS256),
+                    EncodingKey::from_secret(secret.as_bytes()),
+                )
+            }
+            JwtAlgorithm::Rs256 => {
+                let Some(path) = config.signing_key_path.as_deref() else {
+                    return Ok(None);
+                };
+                let pem = fs::read(path).map_err(|e| {
+                    AuthError::Configuration(format!("cannot read signing key '{}': {}", path, e))
+                })?;
+                let key = EncodingKey::from_rsa_pem(&pem).map_err(|e| {
+                    AuthError::Configuration(format!("invalid signing key '{}': {}", path, e))
+                })?;
+                let mut header = Header::new(Algorithm::RS256);
+                header.kid = config.signing_key_id.clone();
+                (header, key)
+            }
+        };
+
+        Ok(Some(Self {
+            header,
+            key,
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+            access_ttl_secs: config.access_token_ttl_secs,
+            refresh_ttl_secs: config.refresh_token_ttl_secs,
+        }))
+    }
+
+    /// Issue an access and a refresh token for `subject` in login session
+    /// `session_id`.
+    pub fn issue(&self, subject: &str, roles: &[String], session_id: &str) -> AuthResult<TokenPair> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| AuthError::Configuration(e.to_string()))?
+            .as_secs();
+
+        let claims = |token_use: &str, ttl: u64| Claims {
+            sub: subject.to_string(),
+            exp: now + ttl,
+            iat: Some(now),
+            jti: Some(Uuid::new_v4().to_string()),
+            token_use: Some(token_use.to_string()),
+            sid: Some(session_id.to_string()),
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
+            roles: roles.to_vec(),
+            scope: None,
+        };
+        let access = claims(ACCESS_TOKEN_USE, self.access_ttl_secs);
+        let refresh = claims(REFRESH_TOKEN_USE, self.refresh_ttl_secs);
+        let refresh_token_id = refresh.jti.clone().unwrap_or_default();
+
+        Ok(TokenPair {
+            access_token: self.sign(&access)?,
+            refresh_token: self.sign(&refresh)?,
+            token_type: "Bearer".to_string(),
+            expires_in: self.access_ttl_secs,
+            refresh_token_id,
+        })
+    }
+
+    fn sign(&self, claims: &Claims) -> AuthResult<String> {
+        encode(&self.header, claims, &self.key)
+            .map_err(|e| AuthError::Configuration(format!("cannot sign token: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JwtVerifier;
+
+    fn config() -> AuthConfig {
+        AuthConfig {
+            jwt_secret: Some("test-secret-that-is-at-least-32-bytes".to_string()),
+            issuer: Some("users-api".to_string()),
+            audience: Some("users".to_string()),
+            ..AuthConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_issued_tokens_verify() {
+        let issuer = TokenIssuer::from_config(&config()).unwrap().unwrap();
+        let verifier = JwtVerifier::from_config(&config()).unwrap();
+
+        let pair = issuer.issue("jane", &["user".to_string()], "session-1").unwrap();
+        let principal = verifier.verify(&pair.access_token).unwrap();
+        assert_eq!(principal.subject, "jane");
+        assert!(principal.has_role("user"));
+
+        let refresh = verifier.verify_refresh(&pair.refresh_token).unwrap();
+        assert_eq!(refresh.jti.as_deref(), Some(pair.refresh_token_id.as_str()));
+        assert_eq!(refresh.sid.as_deref(), Some("session-1"));
+
+        // Each token is only good for its own purpose.
+        assert!(verifier.verify(&pair.refresh_token).is_err());
+        assert!(verifier.verify_refresh(&pair.access_token).is_err());
+    }
+
+    #[test]
+    fn test_rs256_without_signing_key_only_verifies() {
+        let config = AuthConfig {
+            algorithm: JwtAlgorithm::Rs256,
+            jwks_path: Some("fixtures/test_jwks.json".to_string()),
+            ..AuthConfig::default()
+        };
+        assert!(TokenIssuer::from_config(&config).unwrap().is_none());
+
+        let config = AuthConfig {
+            signing_key_path: Some("fixtures/test_rsa_private.pem".to_string()),
+            signing_key_id: Some("test-key-1".to_string()),
+            ..config
+        };
+        let issuer = TokenIssuer::from_config(&config).unwrap().unwrap();
+        let verifier = JwtVerifier::from_config(&config).unwrap();
+        let pair = issuer.issue("jane", &[], "session-1").unwrap();
+        assert!(verifier.verify(&pair.access_token).is_ok());
+    }
+}
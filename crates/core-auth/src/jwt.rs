@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::fs;
+
+use core_config::{AuthConfig, JwtAlgorithm};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+
+use crate::error::{AuthError, AuthResult};
+use crate::principal::{Claims, Principal, REFRESH_TOKEN_USE};
+
+/// Verifies bearer tokens with the algorithm and key material from
+/// [`AuthConfig`]. `HS256` uses the shared secret; `RS256` looks up the
+/// public key by the token's `kid` in a local JWKS file.
+#[derive(Clone)]
+pub struct JwtVerifier {
+    algorithm: Algorithm,
+    keys: VerifierKeys,
+    validation: Validation,
+}
+
+#[derive(Clone)]
+enum VerifierKeys {
+    Secret(DecodingKey),
+    KeySet {
+        by_kid: HashMap<String, DecodingKey>,
+        /// Used for tokens without a `kid` when the set holds a single key.
+        only: Option<DecodingKey>,
+    },
+}
+
+impl JwtVerifier {
+    pub fn from_config(config: &AuthConfig) -> AuthResult<Self> {
+        match config.algorithm {
+            JwtAlgorithm::Hs256 => {
+                let secret = config.jwt_secret.as_deref().ok_or_else(|| {
+                    AuthError::Configuration("auth.jwt_secret is required for HS256".to_string())
+                })?;
+                Ok(Self::hs256(secret.as_bytes(), config))
+            }
+            JwtAlgorithm::Rs256 => {
+                let path = config.jwks_path.as_deref().ok_or_else(|| {
+                    AuthError::Configuration("auth.jwks_path is required for RS256".to_string())
+                })?;
+                let contents = fs::read_to_string(path).map_err(|e| {
+                    AuthError::Configuration(format!("cannot read JWKS file '{}': {}", path, e))
+                })?;
+                let jwks: JwkSet = serde_json::from_str(&contents).map_err(|e| {
+                    AuthError::Configuration(format!("invalid JWKS file '{}': {}", path, e))
+                })?;
+                Self::rs256(&jwks, config)
+            }
+        }
+    }
+
+    pub fn hs256(secret: &[u8], config: &AuthConfig) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            keys: VerifierKeys::Secret(DecodingKey::from_secret(secret)),
+            validation: validation(Algorithm::HS256, config),
+        }
+    }
+
+    pub fn rs256(jwks: &JwkSet, config: &AuthConfig) -> AuthResult<Self> {
+        let keys = jwks
+            .keys
+            .iter()
+            .map(|jwk| Ok((jwk.common.key_id.clone(), decoding_key(jwk)?)))
+            .collect::<AuthResult<Vec<_>>>()?;
+
+        if keys.is_empty() {
+            return Err(AuthError::Configuration("JWKS contains no keys".to_string()));
+        }
+
+        let only = match keys.as_slice() {
+            [(_, key)] => Some(key.clone()),
+            _ => None,
+        };
+        let by_kid = keys
+            .into_iter()
+            .filter_map(|(kid, key)| kid.map(|kid| (kid, key)))
+            .collect();
+
+        Ok(Self {
+            algorithm: Algorithm::RS256,
+            keys: VerifierKeys::KeySet { by_kid, only },
+            validation: validation(Algorithm::RS256, config),
+        })
+    }
+
+    /// Check the signature, `exp`, and the configured `iss`/`aud` of an
+    /// access token. Refresh tokens are rejected.
+    pub fn verify(&self, token: &str) -> AuthResult<Principal> {
+        let claims = self.decode(token)?;
+        if claims.token_use.as_deref() == Some(REFRESH_TOKEN_USE) {
+            return Err(AuthError::InvalidToken(
+                "refresh tokens cannot be used as bearer tokens".to_string(),
+            ));
+        }
+        Ok(claims.into())
+    }
+
+    /// Verify a refresh token and return its claims.
+    pub fn verify_refresh(&self, token: &str) -> AuthResult<Claims> {
+        let claims = self.decode(token)?;
+        if claims.token_use.as_deref() != Some(REFRESH_TOKEN_USE) {
+            return Err(AuthError::InvalidToken("not a refresh token".to_string()));
+        }
+        Ok(claims)
+    }
+
+    fn decode(&self, token: &str) -> AuthResult<Claims> {
+        let header = decode_header(token)?;
+        if header.alg != self.algorithm {
+            return Err(AuthError::InvalidToken(format!(
+                "unexpected algorithm {:?}",
+                header.alg
+            )));
+        }
+
+        let key = match &self.keys {
+            VerifierKeys::Secret(key) => key,
+            VerifierKeys::KeySet { by_kid, only } => match header.kid.as_deref() {
+                Some(kid) => by_kid
+                    .get(kid)
+                    .ok_or_else(|| AuthError::InvalidToken(format!("unknown key id '{}'", kid)))?,
+                None => only
+                    .as_ref()
+                    .ok_or_else(|| AuthError::InvalidToken("missing key id".to_string()))?,
+            },
+        };
+
+        Ok(decode::<Claims>(token, key, &self.validation)?.claims)
+    }
+}
+
+fn decoding_key(jwk: &Jwk) -> AuthResult<DecodingKey> {
+    DecodingKey::from_jwk(jwk)
+        .map_err(|e| AuthError::Configuration(format!("invalid key in JWKS: {}", e)))
+}
+
+fn validation(algorithm: Algorithm, config: &AuthConfig) -> Validation {
+    let mut validation = Validation::new(algorithm);
+    validation.leeway = config.leeway_secs;
+    validation.set_required_spec_claims(&["exp", "sub"]);
+
+    if let Some(issuer) = &config.issuer {
+        validation.set_issuer(&[issuer]);
+    }
+    match &config.audience {
+        Some(audience) => validation.set_audience(&[audience]),
+        None => validation.validate_aud = false,
+    }
+    validation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const SECRET: &str = "test-secret-that-is-at-least-32-bytes";
+    const RSA_PRIVATE_KEY: &str = include_str!("../fixtures/test_rsa_private.pem");
+    const JWKS: &str = include_str!("../fixtures/test_jwks.json");
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn claims(exp: u64) -> Claims {
+        Claims {
+            sub: "6f1c4d3a-2b7e-4c59-9a0d-3e8f1b2c4d5e".to_string(),
+            exp,
+            iat: None,
+            jti: None,
+            token_use: None,
+            sid: None,
+            iss: Some("users-api".to_string()),
+            aud: None,
+            roles: vec!["admin".to_string()],
+            scope: Some("users:read users:write".to_string()),
+        }
+    }
+
+    fn config(algorithm: JwtAlgorithm) -> AuthConfig {
+        AuthConfig {
+            algorithm,
+            jwt_secret: Some(SECRET.to_string()),
+            issuer: Some("users-api".to_string()),
+            leeway_secs: 0,
+            ..AuthConfig::default()
+        }
+    }
+
+    fn hs256_token(claims: &Claims) -> String {
+        encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hs256_token_is_verified() {
+        let verifier = JwtVerifier::from_config(&config(JwtAlgorithm::Hs256)).unwrap();
+
+        let principal = verifier.verify(&hs256_token(&claims(now() + 60))).unwrap();
+        assert!(principal.user_id.is_some());
+        assert!(principal.has_role("admin"));
+        assert_eq!(principal.scopes, vec!["users:read", "users:write"]);
+    }
+
+    #[test]
+    fn test_rejects_expired_and_tampered_tokens() {
+        let verifier = JwtVerifier::from_config(&config(JwtAlgorithm::Hs256)).unwrap();
+
+        assert!(matches!(
+            verifier.verify(&hs256_token(&claims(now() - 60))),
+            Err(AuthError::Expired)
+        ));
+
+        let mut wrong_issuer = claims(now() + 60);
+        wrong_issuer.iss = Some("someone-else".to_string());
+        assert!(matches!(
+            verifier.verify(&hs256_token(&wrong_issuer)),
+            Err(AuthError::InvalidToken(_))
+        ));
+
+        let forged = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(now() + 60),
+            &EncodingKey::from_secret(b"another-secret-that-is-32-bytes-long"),
+        )
+        .unwrap();
+        assert!(matches!(verifier.verify(&forged), Err(AuthError::InvalidToken(_))));
+    }
+
+    #[test]
+    fn test_rs256_token_is_verified_by_kid() {
+        let jwks: JwkSet = serde_json::from_str(JWKS).unwrap();
+        let verifier = JwtVerifier::rs256(&jwks, &config(JwtAlgorithm::Rs256)).unwrap();
+        let key = EncodingKey::from_rsa_pem(RSA_PRIVATE_KEY.as_bytes()).unwrap();
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test-key-1".to_string());
+        let token = encode(&header, &claims(now() + 60), &key).unwrap();
+        assert!(verifier.verify(&token).is_ok());
+
+        header.kid = Some("rotated-away".to_string());
+        let token = encode(&header, &claims(now() + 60), &key).unwrap();
+        assert!(matches!(verifier.verify(&token), Err(AuthError::InvalidToken(_))));
+
+        // An HS256 token must not be accepted by an RS256 verifier.
+        assert!(verifier.verify(&hs256_token(&claims(now() + 60))).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+pub mod api_key;
+pub mod error;
+pub mod issuer;
+pub mod jwt;
+pub mod middleware;
+pub mod principal;
+pub mod rate_limit;
+
+pub use api_key::*;
+pub use error::*;
+pub use issuer::*;
+pub use jwt::*;
+pub use middleware::*;
+pub use principal::*;
+pub use rate_limit::{rate_limit, RateLimiter, RateLimitStore, InMemoryRateLimitStore};
@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api_key::Authenticator;
+use crate::error::AuthError;
+
+/// Reject requests without a valid `Authorization: Bearer` token or API key
+/// and store the caller's [`Principal`](crate::Principal) in the request
+/// extensions.
+///
+/// ```ignore
+/// router.layer(axum::middleware::from_fn_with_state(authenticator, require_auth))
+/// ```
+pub async fn require_auth(
+    State(authenticator): State<Arc<Authenticator>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let principal = match authenticator.authenticate(request.headers()).await {
+        Ok(principal) => principal,
+        Err(e) => return e.into_response(),
+    };
+
+    tracing::debug!(subject = %principal.subject, "Authenticated request");
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
+
+/// Treat every request as coming from [`Principal::system`](crate::Principal::system).
+/// Only for deployments that turn authentication off.
+pub async fn without_auth(mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(crate::Principal::system());
+    next.run(request).await
+}
+
+/// Token from an `Authorization: Bearer <token>` header.
+pub fn bearer_token(headers: &HeaderMap) -> Result<&str, AuthError> {
+    let value = headers
+        .get(header::AUTHORIZATION)
+        .ok_or(AuthError::MissingToken)?
+        .to_str()
+        .map_err(|_| AuthError::InvalidToken("malformed Authorization header".to_string()))?;
+
+    match value.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") && !token.trim().is_empty() => {
+            Ok(token.trim())
+        }
+        _ => Err(AuthError::InvalidToken(
+            "expected 'Authorization: Bearer <token>'".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_key::{ApiKeyVerifier, API_KEY_HEADER};
+    use crate::error::AuthResult;
+    use crate::jwt::JwtVerifier;
+    use crate::principal::{Claims, Principal};
+    use axum::{async_trait, body::Body, http::StatusCode, routing::get, Router};
+    use core_config::AuthConfig;
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use tower::ServiceExt;
+
+    const SECRET: &str = "test-secret-that-is-at-least-32-bytes";
+
+    struct OneKey;
+
+    #[async_trait]
+    impl ApiKeyVerifier for OneKey {
+        async fn verify_api_key(&self, key: &str) -> AuthResult<Principal> {
+            match key {
+                "good-key" => Ok(Principal {
+                    subject: "apikey:billing".to_string(),
+                    user_id: None,
+                    roles: Vec::new(),
+                    scopes: Vec::new(),
+                    expires_at: u64::MAX,
+                }),
+                _ => Err(AuthError::InvalidToken("unknown API key".to_string())),
+            }
+        }
+    }
+
+    fn app() -> Router {
+        let config = AuthConfig {
+            jwt_secret: Some(SECRET.to_string()),
+            ..AuthConfig::default()
+        };
+        let verifier = Arc::new(JwtVerifier::from_config(&config).unwrap());
+        let authenticator = Arc::new(Authenticator::new(verifier).with_api_keys(Arc::new(OneKey)));
+
+        Router::new()
+            .route("/me", get(|principal: Principal| async move { principal.subject }))
+            .layer(axum::middleware::from_fn_with_state(authenticator, require_auth))
+    }
+
+    fn get_me(header_name: &str, value: &str) -> Request {
+        Request::builder()
+            .uri("/me")
+            .header(header_name, value)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn token() -> String {
+        let exp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 60;
+        let claims = Claims {
+            sub: "jane".to_string(),
+            exp,
+            iat: None,
+            jti: None,
+            token_use: None,
+            sid: None,
+            iss: None,
+            aud: None,
+            roles: Vec::new(),
+            scope: None,
+        };
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_is_unauthorized() {
+        let response = app()
+            .oneshot(Request::builder().uri("/me").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], pkg::PROBLEM_JSON);
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_sets_principal() {
+        let response = app()
+            .oneshot(get_me(header::AUTHORIZATION.as_str(), &format!("Bearer {}", token())))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"jane");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_authenticates() {
+        let response = app().oneshot(get_me(API_KEY_HEADER, "good-key")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"apikey:billing");
+
+        // A rejected key is not rescued by a valid bearer token.
+        let mut request = get_me(API_KEY_HEADER, "revoked-key");
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, format!("Bearer {}", token()).parse().unwrap());
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_bearer_token_parsing() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Basic dXNlcjpwYXNz".parse().unwrap());
+        assert!(matches!(bearer_token(&headers), Err(AuthError::InvalidToken(_))));
+
+        headers.insert(header::AUTHORIZATION, "bearer abc.def.ghi".parse().unwrap());
+        assert_eq!(bearer_token(&headers).unwrap(), "abc.def.ghi");
+    }
+}
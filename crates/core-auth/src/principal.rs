@@ -0,0 +1,117 @@
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::AuthError;
+
+/// Role granting every permission.
+pub const ADMIN_ROLE: &str = "admin";
+
+/// Role of every account signing in with a password.
+pub const USER_ROLE: &str = "user";
+
+/// Role of every caller authenticated by an API key.
+pub const SERVICE_ROLE: &str = "service";
+
+/// `token_use` claim of access tokens.
+pub const ACCESS_TOKEN_USE: &str = "access";
+
+/// `token_use` claim of refresh tokens, which are never accepted as bearer tokens.
+pub const REFRESH_TOKEN_USE: &str = "refresh";
+
+/// Claims read from an access token. `roles` and the space-separated
+/// `scope` are optional so tokens from other issuers still verify.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<u64>,
+    /// Unique token ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// `access` or `refresh` for tokens issued by [`TokenIssuer`](crate::TokenIssuer).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_use: Option<String>,
+    /// Login session the token belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// The authenticated caller, stored in the request extensions by
+/// [`require_auth`](crate::require_auth).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub subject: String,
+    /// `sub` parsed as a UUID, when it is one.
+    pub user_id: Option<Uuid>,
+    pub roles: Vec<String>,
+    pub scopes: Vec<String>,
+    /// Expiry of the credential as a Unix timestamp.
+    pub expires_at: u64,
+}
+
+impl Principal {
+    /// A trusted in-process caller holding the admin role, used by the CLI
+    /// and when authentication is disabled by configuration.
+    pub fn system() -> Self {
+        Self {
+            subject: "system".to_string(),
+            user_id: None,
+            roles: vec![ADMIN_ROLE.to_string()],
+            scopes: Vec::new(),
+            expires_at: u64::MAX,
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.has_role(ADMIN_ROLE)
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+impl From<Claims> for Principal {
+    fn from(claims: Claims) -> Self {
+        Self {
+            user_id: Uuid::parse_str(&claims.sub).ok(),
+            subject: claims.sub,
+            roles: claims.roles,
+            scopes: claims
+                .scope
+                .map(|scope| scope.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            expires_at: claims.exp,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Principal
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .ok_or(AuthError::MissingToken)
+    }
+}
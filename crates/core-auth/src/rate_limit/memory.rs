@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use axum::async_trait;
+use core_config::RateLimitRule;
+
+use super::{now_ms, Bucket, RateLimitDecision, RateLimitResult, RateLimitStore};
+
+/// Buckets kept above this count are pruned of the ones that refilled.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Buckets in process memory. Every instance limits on its own, so with
+/// several instances clients get the limit once per instance.
+#[derive(Debug, Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, (Bucket, u64)>>,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn acquire(&self, key: &str, rule: &RateLimitRule) -> RateLimitResult<RateLimitDecision> {
+        let now = now_ms();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        // A full bucket is the same as none, so those can go.
+        if buckets.len() >= PRUNE_THRESHOLD && !buckets.contains_key(key) {
+            buckets.retain(|_, (_, full_at)| *full_at > now);
+        }
+
+        let (bucket, full_at) = buckets
+            .entry(key.to_string())
+            .or_insert_with(|| (Bucket::full(rule, now), now));
+        let decision = bucket.take(rule, now);
+        *full_at = now + decision.reset_secs * 1000;
+        Ok(decision)
+    }
+}
@@ -0,0 +1,407 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use core_config::{RateLimitConfig, RateLimitKey, RateLimitRule, RateLimitStoreKind};
+use pkg::{ErrorCode, ProblemDetails};
+use thiserror::Error;
+
+use crate::principal::{Principal, SERVICE_ROLE};
+
+pub mod memory;
+#[cfg(feature = "redis")]
+pub mod redis;
+
+pub use memory::InMemoryRateLimitStore;
+#[cfg(feature = "redis")]
+pub use self::redis::RedisRateLimitStore;
+
+pub const RATE_LIMIT_LIMIT_HEADER: HeaderName = HeaderName::from_static("ratelimit-limit");
+pub const RATE_LIMIT_REMAINING_HEADER: HeaderName = HeaderName::from_static("ratelimit-remaining");
+pub const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+#[derive(Error, Debug)]
+pub enum RateLimitError {
+    #[error("Rate limit store failed: {0}")]
+    Store(String),
+
+    #[error("Rate limiting is misconfigured: {0}")]
+    Configuration(String),
+}
+
+pub type RateLimitResult<T> = Result<T, RateLimitError>;
+
+/// Outcome of taking a token from a bucket, reported in the `RateLimit-*` headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the bucket is full again.
+    pub reset_secs: u64,
+    /// Seconds until a token is available; zero when the request was allowed.
+    pub retry_after_secs: u64,
+}
+
+impl RateLimitDecision {
+    /// Decision for a bucket left holding `tokens`.
+    pub fn new(rule: &RateLimitRule, allowed: bool, tokens: f64) -> Self {
+        let per_sec = rule.per_minute as f64 / 60.0;
+        let retry_after_secs = if allowed {
+            0
+        } else {
+            ((1.0 - tokens) / per_sec).ceil().max(1.0) as u64
+        };
+
+        Self {
+            allowed,
+            limit: rule.burst,
+            remaining: tokens.max(0.0).floor() as u32,
+            reset_secs: ((rule.burst as f64 - tokens) / per_sec).ceil().max(0.0) as u64,
+            retry_after_secs,
+        }
+    }
+
+    fn write_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(self.limit));
+        headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(self.remaining));
+        headers.insert(RATE_LIMIT_RESET_HEADER, HeaderValue::from(self.reset_secs));
+        if !self.allowed {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(self.retry_after_secs));
+        }
+    }
+}
+
+/// A token bucket as kept by the stores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub tokens: f64,
+    pub updated_ms: u64,
+}
+
+impl Bucket {
+    pub fn full(rule: &RateLimitRule, now_ms: u64) -> Self {
+        Self {
+            tokens: rule.burst as f64,
+            updated_ms: now_ms,
+        }
+    }
+
+    /// Refill for the time elapsed since the last update, then take a token
+    /// if there is one.
+    pub fn take(&mut self, rule: &RateLimitRule, now_ms: u64) -> RateLimitDecision {
+        let per_ms = rule.per_minute as f64 / 60_000.0;
+        let elapsed = now_ms.saturating_sub(self.updated_ms) as f64;
+        self.tokens = (self.tokens + elapsed * per_ms).min(rule.burst as f64);
+        self.updated_ms = self.updated_ms.max(now_ms);
+
+        let allowed = self.tokens >= 1.0;
+        if allowed {
+            self.tokens -= 1.0;
+        }
+        RateLimitDecision::new(rule, allowed, self.tokens)
+    }
+}
+
+/// Where buckets are kept.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Take a token from bucket `key`, which starts out full.
+    async fn acquire(&self, key: &str, rule: &RateLimitRule) -> RateLimitResult<RateLimitDecision>;
+}
+
+/// Applies `server.rate_limit` to requests. See [`rate_limit`].
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self { config, store }
+    }
+
+    /// The limiter described by `config`, connected to its store; `None`
+    /// when rate limiting is disabled.
+    pub async fn from_config(config: &RateLimitConfig) -> RateLimitResult<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let store: Arc<dyn RateLimitStore> = match config.store {
+            RateLimitStoreKind::Memory => Arc::new(InMemoryRateLimitStore::new()),
+            #[cfg(feature = "redis")]
+            RateLimitStoreKind::Redis => {
+                let url = config.redis_url.as_deref().ok_or_else(|| {
+                    RateLimitError::Configuration("server.rate_limit.redis_url is not set".to_string())
+                })?;
+                Arc::new(RedisRateLimitStore::connect(url, &config.redis_key_prefix).await?)
+            }
+            #[cfg(not(feature = "redis"))]
+            RateLimitStoreKind::Redis => {
+                return Err(RateLimitError::Configuration(
+                    "the redis store needs core-auth's `redis` feature".to_string(),
+                ))
+            }
+        };
+        Ok(Some(Self::new(config.clone(), store)))
+    }
+
+    /// Take a token from the bucket `key` of `rule`.
+    pub async fn check(&self, key: &str, rule: &RateLimitRule) -> RateLimitResult<RateLimitDecision> {
+        self.store.acquire(key, rule).await
+    }
+
+    /// The bucket `request` counts against and the rule it matches.
+    pub fn bucket_for(&self, request: &Request) -> (String, RateLimitRule) {
+        let method = request.method().as_str();
+        let path = request.uri().path();
+        let (scope, rule) = match self.config.routes.iter().find(|r| r.matches(method, path)) {
+            Some(route) => (
+                format!("{} {}", route.method.as_deref().unwrap_or("*"), route.path),
+                route.rule(),
+            ),
+            None => ("default".to_string(), self.config.default.clone()),
+        };
+
+        (format!("{}|{}", scope, self.client_key(rule.key, request)), rule)
+    }
+
+    fn client_key(&self, kind: RateLimitKey, request: &Request) -> String {
+        // Only credentials `require_auth` verified count: a header alone would
+        // let clients pick a fresh bucket for every request.
+        let principal = request.extensions().get::<Principal>();
+        let caller = match kind {
+            RateLimitKey::Principal => principal,
+            RateLimitKey::ApiKey => principal.filter(|p| p.has_role(SERVICE_ROLE)),
+            RateLimitKey::Ip => None,
+        };
+        if let Some(caller) = caller {
+            return format!("sub:{}", caller.subject);
+        }
+
+        match self.client_ip(request) {
+            Some(ip) => format!("ip:{}", ip),
+            None => "ip:unknown".to_string(),
+        }
+    }
+
+    /// Each trusted proxy appends the address it got the request from, so the
+    /// client is `trusted_proxy_hops` entries from the right of
+    /// `X-Forwarded-For`. Entries further left come from the client itself.
+    fn client_ip(&self, request: &Request) -> Option<IpAddr> {
+        let hops = self.config.trusted_proxy_hops;
+        let forwarded = if hops == 0 {
+            None
+        } else {
+            let entries: Vec<&str> = request
+                .headers()
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .collect();
+            entries
+                .len()
+                .checked_sub(hops)
+                .and_then(|i| entries[i].trim().parse().ok())
+        };
+
+        forwarded.or_else(|| {
+            request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip())
+        })
+    }
+}
+
+/// Middleware answering `429 Too Many Requests` with `Retry-After` once a
+/// client's bucket is empty, and adding `RateLimit-*` headers to every
+/// response. The client IP comes from the connection, so serve the app with
+/// `into_make_service_with_connect_info::<SocketAddr>()`.
+///
+/// Callers are only recognised by principal when this runs after
+/// [`require_auth`](crate::require_auth). If the store fails, requests are
+/// let through rather than taking the API down with it.
+///
+/// ```ignore
+/// router.route_layer(axum::middleware::from_fn_with_state(limiter, rate_limit))
+/// ```
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (key, rule) = limiter.bucket_for(&request);
+    let decision = match limiter.check(&key, &rule).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            tracing::warn!("Rate limiting skipped: {}", e);
+            return next.run(request).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        tracing::debug!(path = %request.uri().path(), "Rate limit exceeded");
+        ProblemDetails::new(ErrorCode::RateLimited)
+            .with_detail(format!(
+                "Rate limit exceeded, retry in {} seconds",
+                decision.retry_after_secs
+            ))
+            .into_response()
+    };
+    decision.write_headers(response.headers_mut());
+    response
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use core_config::RateLimitRoute;
+    use tower::ServiceExt;
+
+    fn rule(burst: u32, per_minute: u32) -> RateLimitRule {
+        RateLimitRule {
+            key: RateLimitKey::Ip,
+            burst,
+            per_minute,
+        }
+    }
+
+    fn app(config: RateLimitConfig) -> Router {
+        let limiter = Arc::new(RateLimiter::new(config, Arc::new(InMemoryRateLimitStore::new())));
+        Router::new()
+            .route("/login", get(|| async { "ok" }))
+            .route("/users", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(limiter, rate_limit))
+    }
+
+    fn get_from(uri: &str, ip: &str) -> Request {
+        Request::builder()
+            .uri(uri)
+            .header("x-forwarded-for", ip)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let rule = rule(2, 60);
+        let mut bucket = Bucket::full(&rule, 0);
+
+        assert!(bucket.take(&rule, 0).allowed);
+        assert!(bucket.take(&rule, 0).allowed);
+        let denied = bucket.take(&rule, 0);
+        assert!(!denied.allowed);
+        assert_eq!((denied.remaining, denied.retry_after_secs, denied.reset_secs), (0, 1, 2));
+
+        // One token per second comes back, never more than the burst.
+        assert!(bucket.take(&rule, 1_000).allowed);
+        assert!(!bucket.take(&rule, 1_500).allowed);
+        assert_eq!(bucket.take(&rule, 60_000).remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_routes_and_clients_have_separate_buckets() {
+        let config = RateLimitConfig {
+            trusted_proxy_hops: 1,
+            default: rule(5, 60),
+            routes: vec![RateLimitRoute {
+                path: "/login".to_string(),
+                method: Some("GET".to_string()),
+                key: RateLimitKey::Ip,
+                burst: 1,
+                per_minute: 1,
+            }],
+            ..RateLimitConfig::default()
+        };
+        let app = app(config);
+
+        let response = app.clone().oneshot(get_from("/login", "10.0.0.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[&RATE_LIMIT_LIMIT_HEADER], "1");
+        assert_eq!(response.headers()[&RATE_LIMIT_REMAINING_HEADER], "0");
+
+        let response = app.clone().oneshot(get_from("/login", "10.0.0.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "60");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], pkg::PROBLEM_JSON);
+
+        let response = app.clone().oneshot(get_from("/login", "10.0.0.2")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(get_from("/users", "10.0.0.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[&RATE_LIMIT_REMAINING_HEADER], "4");
+    }
+
+    #[tokio::test]
+    async fn test_client_ip_is_counted_from_the_right() {
+        let app = app(RateLimitConfig {
+            trusted_proxy_hops: 2,
+            default: rule(1, 1),
+            ..RateLimitConfig::default()
+        });
+
+        // Addresses left of the trusted hops are up to the client.
+        let spoofed = |ip: &str| get_from("/users", &format!("{}, 10.0.0.1, 192.168.0.1", ip));
+        let response = app.clone().oneshot(spoofed("1.1.1.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.clone().oneshot(spoofed("2.2.2.2")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let response = app.oneshot(get_from("/users", "10.0.0.2, 192.168.0.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_unverified_api_keys_are_counted_by_ip() {
+        let app = app(RateLimitConfig {
+            trusted_proxy_hops: 1,
+            default: RateLimitRule {
+                key: RateLimitKey::ApiKey,
+                burst: 1,
+                per_minute: 1,
+            },
+            ..RateLimitConfig::default()
+        });
+
+        for (key, status) in [("uk_one", StatusCode::OK), ("uk_two", StatusCode::TOO_MANY_REQUESTS)] {
+            let mut request = get_from("/users", "10.0.0.1");
+            request.headers_mut().insert(crate::API_KEY_HEADER, key.parse().unwrap());
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_for_is_ignored_unless_trusted() {
+        let app = app(RateLimitConfig {
+            default: rule(1, 1),
+            ..RateLimitConfig::default()
+        });
+
+        let response = app.clone().oneshot(get_from("/users", "10.0.0.1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(get_from("/users", "10.0.0.2")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,77 @@
+use axum::async_trait;
+use core_config::RateLimitRule;
+use redis::{aio::ConnectionManager, Script};
+
+use super::{now_ms, RateLimitDecision, RateLimitError, RateLimitResult, RateLimitStore};
+
+/// [`Bucket::take`](super::Bucket::take) as one atomic script. Buckets
+/// expire once they would be full again.
+const TAKE_SCRIPT: &str = r#"
+local burst = tonumber(ARGV[1])
+local per_ms = tonumber(ARGV[2]) / 60000
+local now = tonumber(ARGV[3])
+
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'updated_ms')
+local tokens = tonumber(state[1]) or burst
+local updated = tonumber(state[2]) or now
+tokens = math.min(burst, tokens + math.max(0, now - updated) * per_ms)
+
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated_ms', math.max(now, updated))
+redis.call('PEXPIRE', KEYS[1], math.ceil((burst - tokens) / per_ms) + 1000)
+return {allowed, tostring(tokens)}
+"#;
+
+/// Buckets shared by every instance through Redis or a server speaking its
+/// protocol (Valkey, KeyDB, Dragonfly, ...). Instances use their own clocks,
+/// which should be kept in sync.
+#[derive(Clone)]
+pub struct RedisRateLimitStore {
+    connection: ConnectionManager,
+    prefix: String,
+    script: Script,
+}
+
+impl RedisRateLimitStore {
+    /// Connect to `url`, e.g. `redis://localhost:6379/0`, keeping buckets
+    /// under keys starting with `prefix`.
+    pub async fn connect(url: &str, prefix: &str) -> RateLimitResult<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| RateLimitError::Configuration(format!("invalid redis_url: {}", e)))?;
+        let connection = ConnectionManager::new(client)
+            .await
+            .map_err(|e| RateLimitError::Store(e.to_string()))?;
+
+        Ok(Self {
+            connection,
+            prefix: prefix.to_string(),
+            script: Script::new(TAKE_SCRIPT),
+        })
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn acquire(&self, key: &str, rule: &RateLimitRule) -> RateLimitResult<RateLimitDecision> {
+        let mut connection = self.connection.clone();
+        let (allowed, tokens): (i64, String) = self
+            .script
+            .key(format!("{}{}", self.prefix, key))
+            .arg(rule.burst)
+            .arg(rule.per_minute)
+            .arg(now_ms())
+            .invoke_async(&mut connection)
+            .await
+            .map_err(|e| RateLimitError::Store(e.to_string()))?;
+
+        let tokens = tokens
+            .parse()
+            .map_err(|_| RateLimitError::Store(format!("unexpected bucket state '{}'", tokens)))?;
+        Ok(RateLimitDecision::new(rule, allowed == 1, tokens))
+    }
+}
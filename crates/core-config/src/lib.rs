@@ -1,10 +1,126 @@
-use serde::Deserialize;
+use config::{Config, File};
+use serde::{Deserialize, Serialize};
 use std::env;
+use std::path::Path;
 
-#[derive(Debug, Clone, Deserialize)]
+pub mod check;
+pub mod sources;
+
+pub use check::{ConfigEntry, ConfigIssue, ConfigSources};
+pub use sources::EnvOverrides;
+
+use check::IssueCollector;
+
+/// Directory holding the layered configuration files, relative to the
+/// working directory unless overridden with `APP_CONFIG_DIR`.
+pub const DEFAULT_CONFIG_DIR: &str = "config";
+
+/// Profile used when `APP_ENV` is not set.
+pub const DEFAULT_ENVIRONMENT: &str = "development";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DatabaseConfig {
+    /// Primary database, used for writes, transactions and migrations.
     pub database_url: String,
+    /// Read replicas for read-only queries; empty sends everything to the primary.
+    pub replica_urls: Vec<String>,
     pub max_connections: u32,
+    pub min_connections: u32,
+    /// How long to wait for a free connection (or a new one) before failing.
+    pub acquire_timeout_secs: u64,
+    /// Close connections idle for longer than this; `0` keeps them open.
+    pub idle_timeout_secs: u64,
+    /// Recycle connections older than this; `0` never recycles.
+    pub max_lifetime_secs: u64,
+    /// Prepared statements cached per connection.
+    pub statement_cache_capacity: usize,
+    /// Reported to Postgres as `application_name` (visible in `pg_stat_activity`).
+    pub application_name: Option<String>,
+    /// Overrides any `sslmode` given in the URL.
+    pub ssl_mode: Option<DatabaseSslMode>,
+    /// CA certificate used to verify the server with `verify-ca`/`verify-full`.
+    pub ssl_root_cert: Option<String>,
+    /// Schema search path set on every connection, e.g. `"app, public"`.
+    pub search_path: Option<String>,
+    /// Create the pool without connecting; the first query connects.
+    pub lazy_connect: bool,
+    /// Retries of the initial connection when `lazy_connect` is off.
+    pub connect_retry: RetryConfig,
+}
+
+/// Exponential backoff with jitter for establishing connections at startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Attempts before giving up; `0` retries until the deadline.
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// Factor applied to the delay after each failed attempt.
+    pub multiplier: f64,
+    /// Fraction of each delay randomised, e.g. `0.2` waits 80%–120% of it.
+    pub jitter: f64,
+    /// Overall time budget across all attempts.
+    pub deadline_secs: u64,
+}
+
+impl RetryConfig {
+    /// A single attempt, no retries.
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    fn collect_issues(&self, prefix: &str, issues: &mut IssueCollector) {
+        issues.check(
+            self.initial_delay_ms <= self.max_delay_ms,
+            &format!("{}.initial_delay_ms", prefix),
+            format!("must not exceed max_delay_ms ({})", self.max_delay_ms),
+        );
+        issues.check(
+            self.multiplier >= 1.0,
+            &format!("{}.multiplier", prefix),
+            "must be at least 1.0",
+        );
+        issues.check(
+            (0.0..=1.0).contains(&self.jitter),
+            &format!("{}.jitter", prefix),
+            "must be between 0.0 and 1.0",
+        );
+        issues.check(
+            self.deadline_secs > 0,
+            &format!("{}.deadline_secs", prefix),
+            "must be greater than 0",
+        );
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 0,
+            initial_delay_ms: 250,
+            max_delay_ms: 10_000,
+            multiplier: 2.0,
+            jitter: 0.2,
+            deadline_secs: 60,
+        }
+    }
+}
+
+/// Mirrors libpq's `sslmode` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DatabaseSslMode {
+    Disable,
+    Allow,
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
 }
 
 impl DatabaseConfig {
@@ -18,26 +134,449 @@ impl DatabaseConfig {
             .parse()
             .map_err(|_| ConfigError::InvalidValue("DATABASE_MAX_CONNECTIONS".to_string()))?;
 
+        let acquire_timeout_secs = env::var("DATABASE_CONNECT_TIMEOUT")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("DATABASE_CONNECT_TIMEOUT".to_string()))?;
+
         Ok(Self {
             database_url,
             max_connections,
+            acquire_timeout_secs,
+            ..Self::default()
         })
     }
 }
 
+impl DatabaseConfig {
+    fn collect_issues(&self, issues: &mut IssueCollector) {
+        if let Some(problem) = check::postgres_url_problem(&self.database_url) {
+            issues.push("database.database_url", problem);
+        }
+        for (index, url) in self.replica_urls.iter().enumerate() {
+            if let Some(problem) = check::postgres_url_problem(url) {
+                issues.push("database.replica_urls", format!("entry {} {}", index, problem));
+            }
+        }
+        issues.check(
+            self.max_connections > 0,
+            "database.max_connections",
+            "must be greater than 0",
+        );
+        issues.check(
+            self.min_connections <= self.max_connections,
+            "database.min_connections",
+            format!("must not exceed max_connections ({})", self.max_connections),
+        );
+        issues.check(
+            self.acquire_timeout_secs > 0,
+            "database.acquire_timeout_secs",
+            "must be greater than 0",
+        );
+        if let Some(cert) = &self.ssl_root_cert {
+            issues.check(
+                Path::new(cert).is_file(),
+                "database.ssl_root_cert",
+                format!("file '{}' does not exist", cert),
+            );
+        }
+        if let Some(search_path) = &self.search_path {
+            issues.check(
+                !search_path.trim().is_empty(),
+                "database.search_path",
+                "must not be empty when set",
+            );
+        }
+        self.connect_retry.collect_issues("database.connect_retry", issues);
+    }
+}
+
 impl Default for DatabaseConfig {
     fn default() -> Self {
         Self {
             database_url: "postgres://localhost/repository_pattern".to_string(),
+            replica_urls: Vec::new(),
             max_connections: 10,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            statement_cache_capacity: 100,
+            application_name: None,
+            ssl_mode: None,
+            ssl_root_cert: None,
+            search_path: None,
+            lazy_connect: false,
+            connect_retry: RetryConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MongoConfig {
+    pub uri: String,
+    /// Database to use; defaults to the one named in the URI path.
+    pub database: Option<String>,
+    // The options below override the URI's when set and otherwise leave
+    // the URI (or the driver default) in charge.
+    pub min_pool_size: Option<u32>,
+    pub max_pool_size: Option<u32>,
+    pub connect_timeout_secs: Option<u64>,
+    pub server_selection_timeout_secs: Option<u64>,
+    pub app_name: Option<String>,
+    /// Read concern level: `local`, `available`, `majority`, `linearizable` or `snapshot`.
+    pub read_concern: Option<String>,
+    /// Write acknowledgment: `majority`, a node count such as `"1"`, or a tag set name.
+    pub write_concern: Option<String>,
+    /// Require writes to be journaled before they are acknowledged.
+    pub write_journal: Option<bool>,
+    pub write_timeout_secs: Option<u64>,
+    /// Skip the startup `ping`; the driver connects on the first operation.
+    pub lazy_connect: bool,
+    /// Retries of the startup `ping` when `lazy_connect` is off.
+    pub connect_retry: RetryConfig,
+}
+
+impl MongoConfig {
+    fn collect_issues(&self, issues: &mut IssueCollector) {
+        issues.check(
+            self.uri.starts_with("mongodb://") || self.uri.starts_with("mongodb+srv://"),
+            "mongo.uri",
+            "must start with mongodb:// or mongodb+srv://",
+        );
+        match &self.database {
+            Some(database) => {
+                issues.check(!database.trim().is_empty(), "mongo.database", "must not be empty");
+            }
+            None => issues.check(
+                mongo_uri_database(&self.uri).is_some(),
+                "mongo.database",
+                "must be set when the URI names no database",
+            ),
+        }
+        if let (Some(min), Some(max)) = (self.min_pool_size, self.max_pool_size) {
+            issues.check(
+                min <= max,
+                "mongo.min_pool_size",
+                format!("must not exceed max_pool_size ({})", max),
+            );
+        }
+        issues.check(
+            self.max_pool_size != Some(0),
+            "mongo.max_pool_size",
+            "must be greater than 0",
+        );
+        if let Some(level) = &self.read_concern {
+            issues.check(
+                matches!(
+                    level.as_str(),
+                    "local" | "available" | "majority" | "linearizable" | "snapshot"
+                ),
+                "mongo.read_concern",
+                format!("unknown read concern level '{}'", level),
+            );
+        }
+        self.connect_retry.collect_issues("mongo.connect_retry", issues);
+    }
+}
+
+impl Default for MongoConfig {
+    fn default() -> Self {
+        Self {
+            uri: "mongodb://localhost:27017/repository_pattern".to_string(),
+            database: None,
+            min_pool_size: None,
+            max_pool_size: None,
+            connect_timeout_secs: None,
+            server_selection_timeout_secs: None,
+            app_name: None,
+            read_concern: None,
+            write_concern: None,
+            write_journal: None,
+            write_timeout_secs: None,
+            lazy_connect: false,
+            connect_retry: RetryConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The database in the path of a `mongodb://host/database?options` URI.
+fn mongo_uri_database(uri: &str) -> Option<&str> {
+    let (_, rest) = uri.split_once("://")?;
+    let (_, path) = rest.split_once('/')?;
+    let database = path.split('?').next().unwrap_or_default();
+    (!database.is_empty()).then_some(database)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    pub cors: CorsConfig,
+    pub rate_limit: RateLimitConfig,
+}
+
+/// Cross-origin access for browser clients. Nothing is allowed by default;
+/// the `development` profile allows local front-end dev servers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// `https://app.example.com`, `https://*.example.com` for any subdomain,
+    /// or `*` for any origin (not in production). Empty disables CORS.
+    pub allowed_origins: Vec<String>,
+    /// Methods, or `["*"]` for any.
+    pub allowed_methods: Vec<String>,
+    /// Request headers, or `["*"]` for any.
+    pub allowed_headers: Vec<String>,
+    /// Response headers readable by scripts besides the CORS-safelisted ones.
+    pub exposed_headers: Vec<String>,
+    /// Let browsers send cookies and `Authorization`; needs explicit origins.
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response.
+    pub max_age_secs: u64,
+}
+
+impl CorsConfig {
+    /// Whether `origin`, as sent in the `Origin` header, is allowed.
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|pattern| origin_matches(pattern, origin))
+    }
+
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    fn collect_issues(&self, environment: &str, issues: &mut IssueCollector) {
+        let production = environment == "production";
+
+        for origin in &self.allowed_origins {
+            if origin == "*" {
+                issues.check(
+                    !production,
+                    "server.cors.allowed_origins",
+                    "must list origins instead of '*' in production",
+                );
+                continue;
+            }
+            match origin_problem(origin) {
+                Some(problem) => issues.push(
+                    "server.cors.allowed_origins",
+                    format!("'{}' {}", origin, problem),
+                ),
+                None => issues.check(
+                    !production || origin.starts_with("https://"),
+                    "server.cors.allowed_origins",
+                    format!("'{}' must use https in production", origin),
+                ),
+            }
+        }
+
+        if self.allow_credentials {
+            let wildcard = |values: &[String]| values.iter().any(|v| v == "*");
+            issues.check(
+                !self.allows_any_origin(),
+                "server.cors.allow_credentials",
+                "cannot be combined with allowed_origins = [\"*\"]",
+            );
+            issues.check(
+                !wildcard(&self.allowed_methods) && !wildcard(&self.allowed_headers),
+                "server.cors.allow_credentials",
+                "needs explicit allowed_methods and allowed_headers",
+            );
+        }
+    }
+}
+
+/// `scheme://host[:port]` with nothing after it, the host optionally
+/// starting with `*.`.
+fn origin_problem(origin: &str) -> Option<String> {
+    let concrete = origin.replacen("://*.", "://wildcard.", 1);
+    match url::Url::parse(&concrete) {
+        Err(e) => Some(format!("is not a valid origin: {}", e)),
+        Ok(url) if !matches!(url.scheme(), "http" | "https") => {
+            Some("must use http or https".to_string())
+        }
+        Ok(url) if url.origin().ascii_serialization() != concrete.to_ascii_lowercase() => {
+            Some("must be scheme://host[:port] as browsers send it, without a path or default port".to_string())
+        }
+        Ok(_) => None,
+    }
+}
+
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    let Some((scheme, host)) = pattern.split_once("://*.") else {
+        return pattern.eq_ignore_ascii_case(origin);
+    };
+    let prefix = format!("{}://", scheme);
+    let suffix = format!(".{}", host);
+    if origin.len() <= prefix.len() + suffix.len()
+        || !origin[..prefix.len()].eq_ignore_ascii_case(&prefix)
+        || !origin[origin.len() - suffix.len()..].eq_ignore_ascii_case(&suffix)
+    {
+        return false;
+    }
+
+    // Only subdomain labels may stand in for the `*`.
+    origin[prefix.len()..origin.len() - suffix.len()]
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: strings(&["GET", "POST", "PUT", "DELETE"]),
+            allowed_headers: strings(&["authorization", "content-type", "x-api-key", "x-request-id"]),
+            exposed_headers: strings(&[
+                "x-request-id",
+                "retry-after",
+                "ratelimit-limit",
+                "ratelimit-remaining",
+                "ratelimit-reset",
+            ]),
+            allow_credentials: false,
+            max_age_secs: 600,
+        }
+    }
+}
+
+/// Token-bucket rate limits for the HTTP API. Each bucket holds `burst`
+/// requests and refills at `per_minute`; the first entry of `routes` that
+/// matches a request applies, otherwise `default`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub store: RateLimitStoreKind,
+    /// Required for the `redis` store; any server speaking the Redis protocol works.
+    pub redis_url: Option<String>,
+    pub redis_key_prefix: String,
+    /// Number of proxies in front of the server that append to
+    /// `X-Forwarded-For`. The client IP is the address that many entries from
+    /// the right; 0 ignores the header and uses the connection address.
+    pub trusted_proxy_hops: usize,
+    pub default: RateLimitRule,
+    pub routes: Vec<RateLimitRoute>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitStoreKind {
+    /// Buckets live in the process; each instance limits on its own.
+    Memory,
+    /// Buckets are shared between instances through Redis.
+    Redis,
+}
+
+/// What requests are counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    Ip,
+    /// The verified API key of the caller, falling back to the client IP.
+    ApiKey,
+    /// The authenticated caller, falling back to the client IP.
+    Principal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitRule {
+    pub key: RateLimitKey,
+    pub burst: u32,
+    pub per_minute: u32,
+}
+
+/// A rule for the requests to `path`: an exact path, or a prefix ending in
+/// `*` such as `/api/users/*`. Without `method` every method matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitRoute {
+    pub path: String,
+    #[serde(default)]
+    pub method: Option<String>,
+    pub key: RateLimitKey,
+    pub burst: u32,
+    pub per_minute: u32,
+}
+
+impl RateLimitRoute {
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        let method_matches = self
+            .method
+            .as_deref()
+            .is_none_or(|m| m.eq_ignore_ascii_case(method));
+        let path_matches = match self.path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.path,
+        };
+        method_matches && path_matches
+    }
+
+    pub fn rule(&self) -> RateLimitRule {
+        RateLimitRule {
+            key: self.key,
+            burst: self.burst,
+            per_minute: self.per_minute,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    fn collect_issues(&self, issues: &mut IssueCollector) {
+        if !self.enabled {
+            return;
+        }
+
+        if self.store == RateLimitStoreKind::Redis {
+            issues.check(
+                self.redis_url.as_deref().is_some_and(|url| !url.trim().is_empty()),
+                "server.rate_limit.redis_url",
+                "is required for the redis store",
+            );
+        }
+        check_rate(issues, "server.rate_limit.default", self.default.burst, self.default.per_minute);
+        for (i, route) in self.routes.iter().enumerate() {
+            let prefix = format!("server.rate_limit.routes[{}]", i);
+            issues.check(
+                route.path.starts_with('/'),
+                &format!("{}.path", prefix),
+                "must start with '/'",
+            );
+            check_rate(issues, &prefix, route.burst, route.per_minute);
+        }
+    }
+}
+
+fn check_rate(issues: &mut IssueCollector, prefix: &str, burst: u32, per_minute: u32) {
+    issues.check(burst > 0, &format!("{}.burst", prefix), "must be greater than 0");
+    issues.check(per_minute > 0, &format!("{}.per_minute", prefix), "must be greater than 0");
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            store: RateLimitStoreKind::Memory,
+            redis_url: None,
+            redis_key_prefix: "ratelimit:".to_string(),
+            trusted_proxy_hops: 0,
+            default: RateLimitRule {
+                key: RateLimitKey::Principal,
+                burst: 120,
+                per_minute: 600,
+            },
+            routes: Vec::new(),
+        }
+    }
 }
 
 impl ServerConfig {
@@ -48,7 +587,20 @@ impl ServerConfig {
             .parse()
             .map_err(|_| ConfigError::InvalidValue("SERVER_PORT".to_string()))?;
 
-        Ok(Self { host, port })
+        Ok(Self {
+            host,
+            port,
+            ..Self::default()
+        })
+    }
+}
+
+impl ServerConfig {
+    fn collect_issues(&self, environment: &str, issues: &mut IssueCollector) {
+        issues.check(!self.host.trim().is_empty(), "server.host", "must not be empty");
+        issues.check(self.port > 0, "server.port", "must be between 1 and 65535");
+        self.cors.collect_issues(environment, issues);
+        self.rate_limit.collect_issues(issues);
     }
 }
 
@@ -57,15 +609,27 @@ impl Default for ServerConfig {
         Self {
             host: "0.0.0.0".to_string(),
             port: 3000,
+            cors: CorsConfig::default(),
+            rate_limit: RateLimitConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
+    /// Active profile, taken from `APP_ENV`.
+    pub environment: String,
     pub database: DatabaseConfig,
+    /// MongoDB connection; `None` unless a `[mongo]` section or `APP__MONGO__*` is set.
+    pub mongo: Option<MongoConfig>,
     pub server: ServerConfig,
+    pub auth: AuthConfig,
+    pub notifications: NotificationConfig,
     pub modules: ModulesConfig,
+    /// Which layer each value was loaded from.
+    #[serde(skip)]
+    pub sources: ConfigSources,
 }
 
 impl AppConfig {
@@ -73,14 +637,380 @@ impl AppConfig {
         dotenvy::dotenv().ok();
 
         Ok(Self {
+            environment: env::var("APP_ENV").unwrap_or_else(|_| DEFAULT_ENVIRONMENT.to_string()),
             database: DatabaseConfig::from_env()?,
+            mongo: None,
             server: ServerConfig::from_env()?,
+            auth: AuthConfig::from_env(),
+            notifications: NotificationConfig::default(),
             modules: ModulesConfig::default(),
+            sources: ConfigSources::default(),
         })
     }
+
+    /// Load the layered configuration, later layers overriding earlier ones:
+    ///
+    /// 1. `config/default.toml`
+    /// 2. `config/{APP_ENV}.toml` (`APP_ENV` defaults to `development`)
+    /// 3. `config/local.toml`, for untracked machine-specific overrides
+    /// 4. legacy env vars (`DATABASE_URL`, `SERVER_PORT`, ...)
+    /// 5. `APP__`-prefixed env vars, e.g. `APP__SERVER__PORT=8080`
+    ///
+    /// Every file is optional. The directory can be changed with `APP_CONFIG_DIR`.
+    pub fn load() -> Result<Self, ConfigError> {
+        let (dir, environment, from_env) = Self::load_target();
+        let mut config = Self::load_from(dir, &environment, EnvOverrides::new())?;
+        if from_env {
+            config.sources.insert("environment", "env APP_ENV");
+        }
+        Ok(config)
+    }
+
+    /// Same as [`AppConfig::load`], but values that cannot be deserialized are
+    /// returned as issues and left at their defaults instead of failing the
+    /// whole load, so `config:check` can report all of them.
+    pub fn load_lenient() -> Result<(Self, Vec<ConfigIssue>), ConfigError> {
+        let (dir, environment, from_env) = Self::load_target();
+        let (mut config, issues) =
+            Self::load_from_lenient(dir, &environment, EnvOverrides::new())?;
+        if from_env {
+            config.sources.insert("environment", "env APP_ENV");
+        }
+        Ok((config, issues))
+    }
+
+    /// Same as [`AppConfig::load`] with an explicit directory, profile and env source.
+    pub fn load_from(
+        dir: impl AsRef<Path>,
+        environment: &str,
+        env: EnvOverrides,
+    ) -> Result<Self, ConfigError> {
+        let (config, sources) = Self::build(dir.as_ref(), environment, env)?;
+
+        let mut app_config: AppConfig = config
+            .try_deserialize()
+            .map_err(|e| ConfigError::Other(e.to_string()))?;
+        app_config.sources = sources;
+
+        Ok(app_config)
+    }
+
+    /// Same as [`AppConfig::load_lenient`] with an explicit directory, profile and env source.
+    pub fn load_from_lenient(
+        dir: impl AsRef<Path>,
+        environment: &str,
+        env: EnvOverrides,
+    ) -> Result<(Self, Vec<ConfigIssue>), ConfigError> {
+        let (config, sources) = Self::build(dir.as_ref(), environment, env)?;
+        let mut value = config.cache;
+        let mut issues = IssueCollector::new(&sources);
+
+        // Drop each offending value and try again, until what is left
+        // deserializes; values that can't be pinned to a key void everything.
+        let mut app_config: AppConfig = loop {
+            match AppConfig::deserialize(value.clone()) {
+                Ok(app_config) => break app_config,
+                Err(config::ConfigError::Type { key: Some(key), unexpected, expected, .. })
+                    if remove_key(&mut value, &key) =>
+                {
+                    issues.push(&key, format!("expected {}, got {}", expected, unexpected));
+                }
+                Err(e) => {
+                    issues.push("configuration", e.to_string());
+                    break AppConfig {
+                        environment: environment.to_string(),
+                        ..AppConfig::default()
+                    };
+                }
+            }
+        };
+
+        let issues = issues.finish();
+        app_config.sources = sources;
+        Ok((app_config, issues))
+    }
+
+    /// Config directory and profile from the environment, and whether
+    /// `APP_ENV` was actually set.
+    fn load_target() -> (String, String, bool) {
+        dotenvy::dotenv().ok();
+
+        let dir = env::var("APP_CONFIG_DIR").unwrap_or_else(|_| DEFAULT_CONFIG_DIR.to_string());
+        let environment = env::var("APP_ENV").ok();
+        let from_env = environment.is_some();
+
+        (
+            dir,
+            environment.unwrap_or_else(|| DEFAULT_ENVIRONMENT.to_string()),
+            from_env,
+        )
+    }
+
+    fn build(
+        dir: &Path,
+        environment: &str,
+        env: EnvOverrides,
+    ) -> Result<(Config, ConfigSources), ConfigError> {
+        let config = Config::builder()
+            .add_source(File::from(dir.join("default")).required(false))
+            .add_source(File::from(dir.join(environment)).required(false))
+            .add_source(File::from(dir.join("local")).required(false))
+            .add_source(env)
+            .set_override("environment", environment)
+            .and_then(|builder| builder.build())
+            .map_err(|e| ConfigError::Other(e.to_string()))?;
+
+        let sources = ConfigSources::from_value(&config.cache);
+        Ok((config, sources))
+    }
+
+    /// Check the loaded values, reporting every problem at once together with
+    /// the file or env var that supplied the offending value.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut issues = IssueCollector::new(&self.sources);
+
+        self.database.collect_issues(&mut issues);
+        if let Some(mongo) = &self.mongo {
+            mongo.collect_issues(&mut issues);
+        }
+        self.server.collect_issues(&self.environment, &mut issues);
+        self.auth.collect_issues(&self.environment, &mut issues);
+        self.notifications.collect_issues(&mut issues);
+
+        let issues = issues.finish();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(issues))
+        }
+    }
+}
+
+/// Minimum length of an HS256 signing secret, in bytes.
+pub const MIN_JWT_SECRET_LEN: usize = 32;
+
+/// Bearer token authentication for the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Turning this off leaves every route public; rejected in production.
+    pub enabled: bool,
+    pub algorithm: JwtAlgorithm,
+    /// Shared secret for `HS256`.
+    pub jwt_secret: Option<String>,
+    /// JSON Web Key Set file with the public keys for `RS256`.
+    pub jwks_path: Option<String>,
+    /// Expected `iss` claim, if any.
+    pub issuer: Option<String>,
+    /// Expected `aud` claim, if any.
+    pub audience: Option<String>,
+    /// Clock skew tolerated when checking `exp` and `nbf`.
+    pub leeway_secs: u64,
+    /// PEM private key used to sign tokens issued by `/api/auth/login` with
+    /// `RS256`. Without it an `RS256` deployment only verifies tokens.
+    pub signing_key_path: Option<String>,
+    /// `kid` header of issued `RS256` tokens, matching a key in the JWKS.
+    pub signing_key_id: Option<String>,
+    pub access_token_ttl_secs: u64,
+    pub refresh_token_ttl_secs: u64,
+    /// Absolute lifetime of a login session; refreshing never extends it.
+    pub session_timeout_hours: u64,
+    /// How long a password reset token stays valid.
+    pub password_reset_ttl_minutes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JwtAlgorithm {
+    #[serde(rename = "HS256")]
+    Hs256,
+    #[serde(rename = "RS256")]
+    Rs256,
+}
+
+impl AuthConfig {
+    fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            jwt_secret: env::var("JWT_SECRET").ok().filter(|s| !s.is_empty()),
+            session_timeout_hours: env::var("SESSION_TIMEOUT")
+                .ok()
+                .and_then(|hours| hours.parse().ok())
+                .unwrap_or(defaults.session_timeout_hours),
+            ..defaults
+        }
+    }
+
+    fn collect_issues(&self, environment: &str, issues: &mut IssueCollector) {
+        if !self.enabled {
+            issues.check(
+                environment != "production",
+                "auth.enabled",
+                "must be true in production",
+            );
+            return;
+        }
+
+        match self.algorithm {
+            JwtAlgorithm::Hs256 => match &self.jwt_secret {
+                None => issues.push("auth.jwt_secret", "is required for HS256"),
+                Some(secret) => issues.check(
+                    secret.len() >= MIN_JWT_SECRET_LEN,
+                    "auth.jwt_secret",
+                    format!("must be at least {} bytes long", MIN_JWT_SECRET_LEN),
+                ),
+            },
+            JwtAlgorithm::Rs256 => match &self.jwks_path {
+                None => issues.push("auth.jwks_path", "is required for RS256"),
+                Some(path) => issues.check(
+                    Path::new(path).is_file(),
+                    "auth.jwks_path",
+                    format!("file '{}' does not exist", path),
+                ),
+            },
+        }
+
+        if let Some(path) = &self.signing_key_path {
+            issues.check(
+                Path::new(path).is_file(),
+                "auth.signing_key_path",
+                format!("file '{}' does not exist", path),
+            );
+        }
+        issues.check(
+            self.access_token_ttl_secs > 0,
+            "auth.access_token_ttl_secs",
+            "must be greater than 0",
+        );
+        issues.check(
+            self.refresh_token_ttl_secs > self.access_token_ttl_secs,
+            "auth.refresh_token_ttl_secs",
+            "must be greater than auth.access_token_ttl_secs",
+        );
+        issues.check(
+            self.session_timeout_hours > 0,
+            "auth.session_timeout_hours",
+            "must be greater than 0",
+        );
+        issues.check(
+            self.password_reset_ttl_minutes > 0,
+            "auth.password_reset_ttl_minutes",
+            "must be greater than 0",
+        );
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            algorithm: JwtAlgorithm::Hs256,
+            jwt_secret: None,
+            jwks_path: None,
+            issuer: None,
+            audience: None,
+            leeway_secs: 30,
+            signing_key_path: None,
+            signing_key_id: None,
+            access_token_ttl_secs: 900,
+            refresh_token_ttl_secs: 14 * 24 * 3600,
+            session_timeout_hours: 24,
+            password_reset_ttl_minutes: 60,
+        }
+    }
+}
+
+/// Outbound email sent on registration, email changes and password resets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub transport: NotificationTransport,
+    /// Sender of every message, e.g. `Users API <no-reply@example.com>`.
+    pub from: String,
+    /// Directory the `file` transport writes `.eml` files to.
+    pub spool_dir: String,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_security: SmtpSecurity,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationTransport {
+    /// Nothing is sent.
+    Disabled,
+    /// Messages are written to `spool_dir`; for development.
+    File,
+    Smtp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain text; only for local relays and test servers.
+    None,
+    StartTls,
+    /// Implicit TLS, usually on port 465.
+    Tls,
+}
+
+impl NotificationConfig {
+    fn collect_issues(&self, issues: &mut IssueCollector) {
+        if self.transport == NotificationTransport::Disabled {
+            return;
+        }
+
+        issues.check(
+            self.from.contains('@'),
+            "notifications.from",
+            "must be an email address",
+        );
+        match self.transport {
+            NotificationTransport::Disabled => {}
+            NotificationTransport::File => issues.check(
+                !self.spool_dir.trim().is_empty(),
+                "notifications.spool_dir",
+                "must not be empty for the file transport",
+            ),
+            NotificationTransport::Smtp => {
+                issues.check(
+                    self.smtp_host.as_deref().is_some_and(|host| !host.trim().is_empty()),
+                    "notifications.smtp_host",
+                    "is required for the smtp transport",
+                );
+                issues.check(
+                    self.smtp_port > 0,
+                    "notifications.smtp_port",
+                    "must be between 1 and 65535",
+                );
+                issues.check(
+                    self.smtp_username.is_some() == self.smtp_password.is_some(),
+                    "notifications.smtp_password",
+                    "smtp_username and smtp_password must be set together",
+                );
+            }
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            transport: NotificationTransport::Disabled,
+            from: "no-reply@localhost".to_string(),
+            spool_dir: "var/mail".to_string(),
+            smtp_host: None,
+            smtp_port: 587,
+            smtp_security: SmtpSecurity::StartTls,
+            smtp_username: None,
+            smtp_password: None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ModulesConfig {
     pub users_enabled: bool,
 }
@@ -103,4 +1033,374 @@ pub enum ConfigError {
 
     #[error("Configuration error: {0}")]
     Other(String),
+
+    #[error("Invalid configuration:\n{}", format_issues(.0))]
+    Invalid(Vec<ConfigIssue>),
+}
+
+fn format_issues(issues: &[ConfigIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| format!("  - {}", issue))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Remove the value at a dotted `key` such as `server.port`; for a list
+/// element like `server.cors.allowed_origins[1]` the whole list goes.
+fn remove_key(value: &mut config::Value, key: &str) -> bool {
+    let key = key.split('[').next().unwrap_or(key);
+    let (parents, leaf) = key.rsplit_once('.').unwrap_or(("", key));
+
+    let mut current = value;
+    for part in parents.split('.').filter(|part| !part.is_empty()) {
+        current = match &mut current.kind {
+            config::ValueKind::Table(table) => match table.get_mut(part) {
+                Some(child) => child,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+
+    match &mut current.kind {
+        config::ValueKind::Table(table) => table.remove(leaf).is_some(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fs;
+
+    fn config_dir(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = env::temp_dir().join(format!("core-config-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, contents) in files {
+            fs::write(dir.join(file), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_load_defaults_without_files() {
+        let dir = config_dir("empty", &[]);
+        let config = AppConfig::load_from(&dir, "test", EnvOverrides::with_vars(HashMap::new()))
+            .unwrap();
+
+        assert_eq!(config.environment, "test");
+        assert_eq!(config.server.port, 3000);
+        assert_eq!(config.database.max_connections, 10);
+        assert!(config.modules.users_enabled);
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let dir = config_dir(
+            "layers",
+            &[
+                (
+                    "default.toml",
+                    "[server]\nport = 4000\nhost = \"127.0.0.1\"\n[database]\nmax_connections = 5\n",
+                ),
+                ("production.toml", "[server]\nport = 5000\n[modules]\nusers_enabled = false\n"),
+                ("local.toml", "[database]\nmax_connections = 7\n"),
+            ],
+        );
+        let env = EnvOverrides::with_vars(HashMap::from([(
+            "APP__SERVER__PORT".to_string(),
+            "6000".to_string(),
+        )]));
+
+        let config = AppConfig::load_from(&dir, "production", env).unwrap();
+
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.server.port, 6000);
+        assert_eq!(config.database.max_connections, 7);
+        assert!(!config.modules.users_enabled);
+        assert_eq!(config.sources.source_of("server.port"), "env APP__SERVER__PORT");
+        assert!(config.sources.source_of("server.host").ends_with("default.toml"));
+    }
+
+    #[test]
+    fn test_validate_reports_every_issue_with_its_source() {
+        let dir = config_dir(
+            "invalid",
+            &[("default.toml", "[database]\nmax_connections = 0\n")],
+        );
+        let env = EnvOverrides::with_vars(HashMap::from([
+            ("DATABASE_URL".to_string(), "mysql://db/app".to_string()),
+            ("APP__SERVER__PORT".to_string(), "0".to_string()),
+            ("JWT_SECRET".to_string(), "x".repeat(MIN_JWT_SECRET_LEN)),
+        ]));
+
+        let config = AppConfig::load_from(&dir, "invalid", env).unwrap();
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+
+        let reported: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.key.as_str(), issue.source.as_str()))
+            .collect();
+        assert_eq!(reported.len(), 3);
+        assert!(reported.contains(&("database.database_url", "env DATABASE_URL")));
+        assert!(reported.contains(&("server.port", "env APP__SERVER__PORT")));
+        assert!(issues[1].source.ends_with("default.toml"));
+    }
+
+    #[test]
+    fn test_lenient_load_reports_values_of_the_wrong_type() {
+        let dir = config_dir(
+            "lenient",
+            &[("default.toml", "[server]\nhost = \"127.0.0.1\"\n[database]\nmax_connections = \"many\"\n")],
+        );
+        let env = || {
+            EnvOverrides::with_vars(HashMap::from([(
+                "APP__SERVER__PORT".to_string(),
+                "abc".to_string(),
+            )]))
+        };
+
+        assert!(AppConfig::load_from(&dir, "lenient", env()).is_err());
+
+        let (config, issues) = AppConfig::load_from_lenient(&dir, "lenient", env()).unwrap();
+        let reported: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.key.as_str(), issue.source.as_str()))
+            .collect();
+        assert_eq!(reported.len(), 2);
+        assert!(reported.contains(&("server.port", "env APP__SERVER__PORT")));
+        assert!(issues.iter().any(|issue| issue.key == "database.max_connections"
+            && issue.source.ends_with("default.toml")));
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.server.port, 3000);
+        assert_eq!(config.sources.source_of("environment"), check::BUILTIN_SOURCE);
+    }
+
+    #[test]
+    fn test_mongo_section_is_optional() {
+        let dir = config_dir("mongo", &[]);
+        let config = AppConfig::load_from(&dir, "mongo", EnvOverrides::with_vars(HashMap::new()))
+            .unwrap();
+        assert!(config.mongo.is_none());
+
+        let env = EnvOverrides::with_vars(HashMap::from([
+            ("APP__MONGO__URI".to_string(), "mongodb://mongo:27017".to_string()),
+            ("APP__MONGO__READ_CONCERN".to_string(), "eventual".to_string()),
+        ]));
+        let config = AppConfig::load_from(&dir, "mongo", env).unwrap();
+        let mongo = config.mongo.as_ref().unwrap();
+        assert_eq!(mongo.uri, "mongodb://mongo:27017");
+        assert_eq!(mongo.database, None);
+        assert_eq!(mongo.max_pool_size, None);
+
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        let keys: Vec<_> = issues.iter().map(|issue| issue.key.as_str()).collect();
+        assert_eq!(keys[..2], ["mongo.database", "mongo.read_concern"]);
+        assert_eq!(mongo_uri_database("mongodb+srv://a,b/users?w=1"), Some("users"));
+        assert_eq!(mongo_uri_database("mongodb://mongo:27017/?w=1"), None);
+    }
+
+    #[test]
+    fn test_connect_retry_from_env() {
+        let dir = config_dir("retry", &[]);
+        let env = EnvOverrides::with_vars(HashMap::from([
+            ("APP__DATABASE__LAZY_CONNECT".to_string(), "true".to_string()),
+            ("APP__DATABASE__CONNECT_RETRY__DEADLINE_SECS".to_string(), "120".to_string()),
+            ("APP__DATABASE__CONNECT_RETRY__MULTIPLIER".to_string(), "0.5".to_string()),
+        ]));
+
+        let config = AppConfig::load_from(&dir, "retry", env).unwrap();
+        assert!(config.database.lazy_connect);
+        assert_eq!(config.database.connect_retry.deadline_secs, 120);
+        assert_eq!(config.database.connect_retry.max_delay_ms, 10_000);
+
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        assert_eq!(issues[0].key, "database.connect_retry.multiplier");
+        assert_eq!(issues[0].source, "env APP__DATABASE__CONNECT_RETRY__MULTIPLIER");
+    }
+
+    #[test]
+    fn test_auth_requires_key_material() {
+        let dir = config_dir("auth", &[]);
+        let env = EnvOverrides::with_vars(HashMap::from([(
+            "JWT_SECRET".to_string(),
+            "too-short".to_string(),
+        )]));
+
+        let config = AppConfig::load_from(&dir, "auth", env).unwrap();
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        assert_eq!(issues[0].key, "auth.jwt_secret");
+        assert_eq!(issues[0].source, "env JWT_SECRET");
+
+        let env = EnvOverrides::with_vars(HashMap::from([
+            ("APP__AUTH__ALGORITHM".to_string(), "RS256".to_string()),
+            ("APP__AUTH__JWKS_PATH".to_string(), "/nonexistent/jwks.json".to_string()),
+        ]));
+        let config = AppConfig::load_from(&dir, "auth", env).unwrap();
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        assert_eq!(issues[0].key, "auth.jwks_path");
+
+        let env = EnvOverrides::with_vars(HashMap::from([
+            ("JWT_SECRET".to_string(), "a-secret-that-is-at-least-32-bytes".to_string()),
+            ("SESSION_TIMEOUT".to_string(), "8".to_string()),
+        ]));
+        let config = AppConfig::load_from(&dir, "auth", env).unwrap();
+        assert_eq!(config.auth.session_timeout_hours, 8);
+        assert!(config.validate().is_ok());
+
+        let env = EnvOverrides::with_vars(HashMap::from([(
+            "APP__AUTH__ENABLED".to_string(),
+            "false".to_string(),
+        )]));
+        let config = AppConfig::load_from(&dir, "auth", env.clone()).unwrap();
+        assert!(config.validate().is_ok());
+        let config = AppConfig::load_from(&dir, "production", env).unwrap();
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        assert_eq!(issues[0].key, "auth.enabled");
+        assert_eq!(issues[0].source, "env APP__AUTH__ENABLED");
+    }
+
+    #[test]
+    fn test_smtp_notifications_require_a_host() {
+        let dir = config_dir("notifications", &[]);
+        let env = EnvOverrides::with_vars(HashMap::from([
+            ("JWT_SECRET".to_string(), "a-secret-that-is-at-least-32-bytes".to_string()),
+            ("APP__NOTIFICATIONS__TRANSPORT".to_string(), "smtp".to_string()),
+            ("APP__NOTIFICATIONS__SMTP_SECURITY".to_string(), "starttls".to_string()),
+        ]));
+
+        let config = AppConfig::load_from(&dir, "notifications", env).unwrap();
+        assert_eq!(config.notifications.smtp_security, SmtpSecurity::StartTls);
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "notifications.smtp_host");
+    }
+
+    #[test]
+    fn test_rate_limit_routes() {
+        let dir = config_dir(
+            "rate-limit",
+            &[(
+                "ratelimit.toml",
+                r#"
+                [server.rate_limit]
+                store = "redis"
+
+                [[server.rate_limit.routes]]
+                path = "/api/auth/login"
+                method = "POST"
+                key = "ip"
+                burst = 5
+                per_minute = 0
+
+                [[server.rate_limit.routes]]
+                path = "/api/users/*"
+                key = "principal"
+                burst = 60
+                per_minute = 120
+                "#,
+            )],
+        );
+        let env = EnvOverrides::with_vars(HashMap::from([(
+            "JWT_SECRET".to_string(),
+            "a-secret-that-is-at-least-32-bytes".to_string(),
+        )]));
+
+        let config = AppConfig::load_from(&dir, "ratelimit", env).unwrap();
+        let routes = &config.server.rate_limit.routes;
+        assert!(routes[0].matches("post", "/api/auth/login"));
+        assert!(!routes[0].matches("GET", "/api/auth/login"));
+        assert!(routes[1].matches("DELETE", "/api/users/42"));
+        assert!(!routes[1].matches("GET", "/api/users"));
+
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        let keys: Vec<_> = issues.iter().map(|issue| issue.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["server.rate_limit.redis_url", "server.rate_limit.routes[0].per_minute"]
+        );
+    }
+
+    #[test]
+    fn test_cors_origins() {
+        let cors = CorsConfig {
+            allowed_origins: vec![
+                "https://app.example.com".to_string(),
+                "https://*.example.org".to_string(),
+            ],
+            ..CorsConfig::default()
+        };
+        assert!(cors.allows_origin("https://APP.example.com"));
+        assert!(cors.allows_origin("https://a.b.example.org"));
+        assert!(!cors.allows_origin("https://example.org"));
+        assert!(!cors.allows_origin("http://a.example.org"));
+        assert!(!cors.allows_origin("https://evil.com/.example.org"));
+        assert!(!cors.allows_origin("https://app.example.com.evil.com"));
+
+        let mut config = AppConfig {
+            environment: "production".to_string(),
+            ..AppConfig::default()
+        };
+        config.auth.jwt_secret = Some("a-secret-that-is-at-least-32-bytes".to_string());
+        config.server.cors = CorsConfig {
+            allowed_origins: vec![
+                "*".to_string(),
+                "http://app.example.com".to_string(),
+                "https://app.example.com/".to_string(),
+            ],
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation to fail");
+        };
+        let keys: Vec<_> = issues.iter().map(|issue| issue.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "server.cors.allowed_origins",
+                "server.cors.allowed_origins",
+                "server.cors.allowed_origins",
+                "server.cors.allow_credentials",
+            ]
+        );
+
+        config.environment = "development".to_string();
+        config.server.cors.allowed_origins = vec!["http://localhost:5173".to_string()];
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_entries_redact_secrets() {
+        let mut config = AppConfig::default();
+        config.database.database_url = "postgres://app:hunter2@db/app".to_string();
+
+        let entries = config.entries();
+        let url = entries
+            .iter()
+            .find(|entry| entry.key == "database.database_url")
+            .unwrap();
+
+        assert!(!url.value.contains("hunter2"));
+        assert_eq!(url.source, check::BUILTIN_SOURCE);
+    }
 }
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::env;
+
+use config::{Map, Source, Value, ValueKind};
+
+/// Prefix of environment variables that override any configuration key,
+/// e.g. `APP__DATABASE__MAX_CONNECTIONS` for `database.max_connections`.
+pub const ENV_PREFIX: &str = "APP";
+
+/// Separator between the prefix and nested key segments in env var names.
+pub const ENV_SEPARATOR: &str = "__";
+
+/// Pre-layered env vars still honoured for existing deployments, mapped to
+/// the configuration key they set. `APP__` variables take precedence.
+pub const LEGACY_ENV_VARS: &[(&str, &str)] = &[
+    ("DATABASE_URL", "database.database_url"),
+    ("DATABASE_MAX_CONNECTIONS", "database.max_connections"),
+    ("DATABASE_CONNECT_TIMEOUT", "database.acquire_timeout_secs"),
+    ("MONGODB_URI", "mongo.uri"),
+    ("SERVER_HOST", "server.host"),
+    ("SERVER_PORT", "server.port"),
+    ("JWT_SECRET", "auth.jwt_secret"),
+    ("SESSION_TIMEOUT", "auth.session_timeout_hours"),
+];
+
+/// Keys holding lists, given in env vars as comma-separated values.
+pub const LIST_KEYS: &[&str] = &["database.replica_urls"];
+
+/// Configuration source reading `APP__`-prefixed and legacy env vars.
+///
+/// Unlike `config::Environment`, every value records the variable it came
+/// from as its origin, so problems can be reported against the right name.
+#[derive(Debug, Clone, Default)]
+pub struct EnvOverrides {
+    vars: Option<HashMap<String, String>>,
+}
+
+impl EnvOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read from the given variables instead of the process environment.
+    pub fn with_vars(vars: HashMap<String, String>) -> Self {
+        Self { vars: Some(vars) }
+    }
+
+    fn vars(&self) -> HashMap<String, String> {
+        match &self.vars {
+            Some(vars) => vars.clone(),
+            None => env::vars().collect(),
+        }
+    }
+
+    /// Configuration key for an `APP__` variable name, if it is one.
+    pub fn key_for(var: &str) -> Option<String> {
+        let rest = var.strip_prefix(ENV_PREFIX)?.strip_prefix(ENV_SEPARATOR)?;
+        if rest.is_empty() {
+            return None;
+        }
+        Some(rest.to_lowercase().replace(ENV_SEPARATOR, "."))
+    }
+
+    fn value(key: &str, raw: &str, origin: &String) -> Value {
+        if !LIST_KEYS.contains(&key) {
+            return Value::new(Some(origin), ValueKind::String(raw.to_string()));
+        }
+
+        let items = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| Value::new(Some(origin), ValueKind::String(item.to_string())))
+            .collect::<Vec<_>>();
+        Value::new(Some(origin), ValueKind::Array(items))
+    }
+}
+
+impl Source for EnvOverrides {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, config::ConfigError> {
+        let vars = self.vars();
+        let mut values = Map::new();
+
+        for (var, key) in LEGACY_ENV_VARS {
+            if let Some(value) = vars.get(*var).filter(|v| !v.is_empty()) {
+                let origin = format!("env {}", var);
+                values.insert(key.to_string(), Self::value(key, value, &origin));
+            }
+        }
+
+        let mut prefixed: Vec<_> = vars
+            .iter()
+            .filter_map(|(var, value)| Self::key_for(var).map(|key| (key, var, value)))
+            .collect();
+        prefixed.sort();
+
+        for (key, var, value) in prefixed {
+            let origin = format!("env {}", var);
+            let value = Self::value(&key, value, &origin);
+            values.insert(key, value);
+        }
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_for() {
+        assert_eq!(
+            EnvOverrides::key_for("APP__DATABASE__MAX_CONNECTIONS").as_deref(),
+            Some("database.max_connections")
+        );
+        assert_eq!(EnvOverrides::key_for("APP_ENV"), None);
+        assert_eq!(EnvOverrides::key_for("DATABASE_URL"), None);
+    }
+
+    #[test]
+    fn test_prefixed_vars_override_legacy() {
+        let source = EnvOverrides::with_vars(HashMap::from([
+            ("DATABASE_URL".to_string(), "postgres://legacy/db".to_string()),
+            ("APP__DATABASE__DATABASE_URL".to_string(), "postgres://layered/db".to_string()),
+        ]));
+
+        let values = source.collect().unwrap();
+        let url = values.get("database.database_url").unwrap();
+        assert_eq!(url.origin(), Some("env APP__DATABASE__DATABASE_URL"));
+        assert_eq!(url.clone().into_string().unwrap(), "postgres://layered/db");
+    }
+
+    #[test]
+    fn test_list_keys_are_split() {
+        let source = EnvOverrides::with_vars(HashMap::from([(
+            "APP__DATABASE__REPLICA_URLS".to_string(),
+            "postgres://r1/db, postgres://r2/db".to_string(),
+        )]));
+
+        let values = source.collect().unwrap();
+        let replicas = values.get("database.replica_urls").unwrap().clone();
+        assert_eq!(replicas.into_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+#[path = "../../core-db/src/file_name.rs"]
+mod file_name;
+
+use file_name::{MigrationDirection, MigrationFileName};
+
+#[derive(Default)]
+struct MigrationFiles {
+    name: String,
+    up: Option<PathBuf>,
+    down: Option<PathBuf>,
+}
+
+/// Embed every `<module>/<version>_<name>.up.sql` (and optional `.down.sql`)
+/// found under a directory, relative to the calling crate's `Cargo.toml`, as a
+/// `&'static [core_db::Migration]`.
+///
+/// Uses the same layout as `core_db::MigrationLoader::load_dir`. Files are read
+/// with `include_str!`, so edits trigger a rebuild; newly added files need the
+/// calling crate to be rebuilt (e.g. `touch` the file using the macro).
+///
+/// ```ignore
+/// pub const MIGRATIONS: &[Migration] = core_db::embed_migrations!("migrations");
+/// ```
+#[proc_macro]
+pub fn embed_migrations(input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(input as LitStr);
+
+    match expand(&dir) {
+        Ok(tokens) => tokens.into(),
+        Err(message) => syn::Error::new(dir.span(), message).to_compile_error().into(),
+    }
+}
+
+fn expand(dir: &LitStr) -> Result<proc_macro2::TokenStream, String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "CARGO_MANIFEST_DIR is not set".to_string())?;
+    let root = Path::new(&manifest_dir).join(dir.value());
+
+    let mut migrations = Vec::new();
+
+    for module_dir in sorted_entries(&root)? {
+        if !module_dir.is_dir() {
+            continue;
+        }
+
+        let module = file_name(&module_dir)?;
+
+        for (version, files) in collect_module(&module, &module_dir)? {
+            let name = files.name;
+            let up = files.up.ok_or_else(|| {
+                format!(
+                    "migration {}:{}_{} has a .down.sql file but no .up.sql",
+                    module, version, name
+                )
+            })?;
+            let up = path_str(&up)?;
+
+            migrations.push(match files.down {
+                Some(down) => {
+                    let down = path_str(&down)?;
+                    quote! {
+                        ::core_db::Migration::reversible(
+                            #module, #version, #name, include_str!(#up), include_str!(#down),
+                        )
+                    }
+                }
+                None => quote! {
+                    ::core_db::Migration::new(#module, #version, #name, include_str!(#up))
+                },
+            });
+        }
+    }
+
+    Ok(quote! { &[#(#migrations),*] })
+}
+
+fn collect_module(module: &str, dir: &Path) -> Result<BTreeMap<i32, MigrationFiles>, String> {
+    let mut files: BTreeMap<i32, MigrationFiles> = BTreeMap::new();
+
+    for path in sorted_entries(dir)? {
+        if !path.is_file() {
+            continue;
+        }
+
+        let Some(parsed) = MigrationFileName::parse(&file_name(&path)?)? else {
+            continue;
+        };
+
+        let entry = files.entry(parsed.version).or_insert_with(|| MigrationFiles {
+            name: parsed.name.clone(),
+            ..Default::default()
+        });
+
+        if entry.name != parsed.name {
+            return Err(format!(
+                "duplicate version {} in module '{}': '{}' and '{}'",
+                parsed.version, module, entry.name, parsed.name
+            ));
+        }
+
+        match parsed.direction {
+            MigrationDirection::Up => entry.up = Some(path),
+            MigrationDirection::Down => entry.down = Some(path),
+        }
+    }
+
+    Ok(files)
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("failed to read migrations directory {}: {}", dir.display(), e))?;
+
+    let mut paths = entries
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    paths.sort();
+    Ok(paths)
+}
+
+fn file_name(path: &Path) -> Result<String, String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("invalid migration path: {}", path.display()))
+}
+
+fn path_str(path: &Path) -> Result<String, String> {
+    path.to_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("non UTF-8 migration path: {}", path.display()))
+}
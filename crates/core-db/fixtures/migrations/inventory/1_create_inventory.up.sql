@@ -0,0 +1,4 @@
+CREATE TABLE inventory (
+    widget_id UUID NOT NULL,
+    quantity INTEGER NOT NULL DEFAULT 0
+);
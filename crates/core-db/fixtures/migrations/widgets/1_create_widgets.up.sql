@@ -0,0 +1,4 @@
+CREATE TABLE widgets (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL
+);
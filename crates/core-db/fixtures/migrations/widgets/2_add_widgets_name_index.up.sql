@@ -0,0 +1 @@
+CREATE INDEX idx_widgets_name ON widgets(name);
@@ -1,7 +1,16 @@
-use sqlx::{PgPool, postgres::PgPoolOptions};
-use core_config::DatabaseConfig;
+use std::str::FromStr;
+use std::time::Duration;
+
+use sqlx::{
+    PgPool,
+    postgres::{PgConnectOptions, PgPoolOptions, PgSslMode},
+};
+use core_config::{DatabaseConfig, DatabaseSslMode};
 use pkg::{RepositoryError, RepositoryResult};
 
+use crate::pools::DatabasePools;
+use crate::retry::retry_with_backoff;
+
 pub struct DatabaseFactory;
 
 impl DatabaseFactory {
@@ -12,12 +21,104 @@ impl DatabaseFactory {
         Self::create_postgres_pool(&config).await
     }
 
+    /// Create the primary pool. With `lazy_connect` the pool is returned
+    /// without connecting; otherwise the first connection is retried with
+    /// backoff according to `connect_retry`.
     pub async fn create_postgres_pool(config: &DatabaseConfig) -> RepositoryResult<PgPool> {
+        let connect_options = Self::postgres_connect_options(config)?;
+        let pool_options = Self::postgres_pool_options(config);
+
+        if config.lazy_connect {
+            tracing::info!("Postgres pool created in lazy mode; connecting on first use");
+            return Ok(pool_options.connect_lazy_with(connect_options));
+        }
+
+        retry_with_backoff(
+            "Postgres",
+            &config.connect_retry,
+            |e| !matches!(e, sqlx::Error::Configuration(_)),
+            |remaining| {
+                let connect = pool_options.clone().connect_with(connect_options.clone());
+                async move {
+                    tokio::time::timeout(remaining, connect)
+                        .await
+                        .unwrap_or(Err(sqlx::Error::PoolTimedOut))
+                }
+            },
+        )
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    /// Connect to the primary and set up a pool per read replica.
+    ///
+    /// Replica pools connect lazily, so an unreachable replica neither blocks
+    /// startup nor fails reads: they fall back to the primary.
+    pub async fn create_database_pools(config: &DatabaseConfig) -> RepositoryResult<DatabasePools> {
+        let primary = Self::create_postgres_pool(config).await?;
+
+        let replicas = config
+            .replica_urls
+            .iter()
+            .map(|url| {
+                let replica_config = DatabaseConfig {
+                    database_url: url.clone(),
+                    ..config.clone()
+                };
+                let options = Self::postgres_connect_options(&replica_config)?;
+                Ok(Self::postgres_pool_options(&replica_config).connect_lazy_with(options))
+            })
+            .collect::<RepositoryResult<Vec<_>>>()?;
+
+        if !replicas.is_empty() {
+            tracing::info!("Configured {} read replica(s)", replicas.len());
+        }
+
+        Ok(DatabasePools::new(primary, replicas))
+    }
+
+    /// Connection-level settings: the URL plus TLS, statement cache,
+    /// `application_name` and `search_path` overrides.
+    pub fn postgres_connect_options(config: &DatabaseConfig) -> RepositoryResult<PgConnectOptions> {
+        let mut options = PgConnectOptions::from_str(&config.database_url)
+            .map_err(|e| RepositoryError::DatabaseError(format!("Invalid database URL: {}", e)))?
+            .statement_cache_capacity(config.statement_cache_capacity);
+
+        if let Some(application_name) = &config.application_name {
+            options = options.application_name(application_name);
+        }
+        if let Some(ssl_mode) = config.ssl_mode {
+            options = options.ssl_mode(Self::pg_ssl_mode(ssl_mode));
+        }
+        if let Some(root_cert) = &config.ssl_root_cert {
+            options = options.ssl_root_cert(root_cert);
+        }
+        if let Some(search_path) = &config.search_path {
+            options = options.options([("search_path", escape_option_value(search_path))]);
+        }
+
+        Ok(options)
+    }
+
+    /// Pool-level settings: sizing, acquire timeout and connection recycling.
+    pub fn postgres_pool_options(config: &DatabaseConfig) -> PgPoolOptions {
         PgPoolOptions::new()
             .max_connections(config.max_connections)
-            .connect(&config.database_url)
-            .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+            .min_connections(config.min_connections)
+            .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+            .idle_timeout(non_zero_secs(config.idle_timeout_secs))
+            .max_lifetime(non_zero_secs(config.max_lifetime_secs))
+    }
+
+    fn pg_ssl_mode(mode: DatabaseSslMode) -> PgSslMode {
+        match mode {
+            DatabaseSslMode::Disable => PgSslMode::Disable,
+            DatabaseSslMode::Allow => PgSslMode::Allow,
+            DatabaseSslMode::Prefer => PgSslMode::Prefer,
+            DatabaseSslMode::Require => PgSslMode::Require,
+            DatabaseSslMode::VerifyCa => PgSslMode::VerifyCa,
+            DatabaseSslMode::VerifyFull => PgSslMode::VerifyFull,
+        }
     }
 
     #[deprecated(
@@ -51,10 +152,84 @@ impl DatabaseFactory {
     }
 }
 
+/// Escape a value for the `options` startup parameter, which Postgres splits
+/// on unescaped whitespace, e.g. `app, public` becomes `app,\ public`.
+fn escape_option_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '\\' || c.is_whitespace() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn non_zero_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_connect_options_apply_config() {
+        let config = DatabaseConfig {
+            database_url: "postgres://app@db.internal:6432/app?sslmode=disable".to_string(),
+            application_name: Some("users-api".to_string()),
+            ssl_mode: Some(DatabaseSslMode::Require),
+            search_path: Some("app, public".to_string()),
+            ..DatabaseConfig::default()
+        };
+
+        let options = DatabaseFactory::postgres_connect_options(&config).unwrap();
+
+        assert_eq!(options.get_host(), "db.internal");
+        assert_eq!(options.get_port(), 6432);
+        assert_eq!(options.get_application_name(), Some("users-api"));
+        assert!(matches!(options.get_ssl_mode(), PgSslMode::Require));
+        assert_eq!(options.get_options(), Some("-c search_path=app,\\ public"));
+    }
+
+    #[test]
+    fn test_zero_timeouts_disable_recycling() {
+        let config = DatabaseConfig {
+            idle_timeout_secs: 0,
+            max_lifetime_secs: 0,
+            ..DatabaseConfig::default()
+        };
+
+        let options = DatabaseFactory::postgres_pool_options(&config);
+        assert_eq!(options.get_idle_timeout(), None);
+        assert_eq!(options.get_max_lifetime(), None);
+
+        let options = DatabaseFactory::postgres_pool_options(&DatabaseConfig::default());
+        assert_eq!(options.get_idle_timeout(), Some(Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn test_connect_options_reject_invalid_url() {
+        let config = DatabaseConfig {
+            database_url: "not a url".to_string(),
+            ..DatabaseConfig::default()
+        };
+
+        assert!(DatabaseFactory::postgres_connect_options(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lazy_pool_does_not_connect() {
+        let config = DatabaseConfig {
+            database_url: "postgres://app@127.0.0.1:1/app".to_string(),
+            lazy_connect: true,
+            ..DatabaseConfig::default()
+        };
+
+        let pool = DatabaseFactory::create_postgres_pool(&config).await.unwrap();
+        assert_eq!(pool.size(), 0);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_create_pool() {
@@ -0,0 +1,56 @@
+// Shared with `core-db-macros`, which includes this file by path, so it must
+// not depend on anything outside `std`.
+
+/// Which half of a migration a `.sql` file holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationDirection {
+    Up,
+    Down,
+}
+
+/// Parsed form of a `<version>_<name>.up.sql` / `.down.sql` file name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationFileName {
+    pub version: i32,
+    pub name: String,
+    pub direction: MigrationDirection,
+}
+
+impl MigrationFileName {
+    /// Parse a migration file name. Returns `None` for files that are not
+    /// `.sql` files, and an error for `.sql` files that don't follow the layout.
+    pub fn parse(file_name: &str) -> Result<Option<Self>, String> {
+        let Some(stem) = file_name.strip_suffix(".sql") else {
+            return Ok(None);
+        };
+
+        let (stem, direction) = if let Some(stem) = stem.strip_suffix(".up") {
+            (stem, MigrationDirection::Up)
+        } else if let Some(stem) = stem.strip_suffix(".down") {
+            (stem, MigrationDirection::Down)
+        } else {
+            return Err(format!(
+                "Migration file '{}' must end with .up.sql or .down.sql",
+                file_name
+            ));
+        };
+
+        let (version, name) = stem
+            .split_once('_')
+            .ok_or_else(|| format!("Migration file '{}' must be named <version>_<name>", file_name))?;
+
+        let version = version.parse::<i32>().map_err(|_| {
+            format!("Migration file '{}' has an invalid version '{}'", file_name, version)
+        })?;
+
+        if name.is_empty() {
+            return Err(format!("Migration file '{}' has an empty name", file_name));
+        }
+
+        Ok(Some(Self {
+            version,
+            name: name.to_string(),
+            direction,
+        }))
+    }
+}
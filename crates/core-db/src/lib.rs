@@ -1,7 +1,23 @@
+extern crate self as core_db;
+
 pub mod factory;
+mod file_name;
+pub mod health;
+pub mod loader;
 pub mod migrations;
+#[cfg(feature = "mongo")]
+pub mod mongo;
+pub mod pools;
+pub mod retry;
 pub mod unit_of_work;
 
 pub use factory::*;
+pub use file_name::*;
+pub use health::*;
+pub use loader::*;
 pub use migrations::*;
+pub use pools::*;
+pub use retry::*;
 pub use unit_of_work::*;
+
+pub use core_db_macros::embed_migrations;
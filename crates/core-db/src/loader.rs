@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use pkg::{RepositoryError, RepositoryResult};
+
+use crate::file_name::{MigrationDirection, MigrationFileName};
+use crate::migrations::Migration;
+
+/// Discovers SQL migrations laid out as `<root>/<module>/<version>_<name>.up.sql`
+/// (with an optional matching `.down.sql`) at runtime.
+///
+/// The compile-time counterpart is [`embed_migrations!`](crate::embed_migrations).
+pub struct MigrationLoader;
+
+impl MigrationLoader {
+    pub fn load_dir(root: impl AsRef<Path>) -> RepositoryResult<Vec<Migration>> {
+        let root = root.as_ref();
+        let mut migrations = Vec::new();
+
+        for module_dir in Self::sorted_entries(root)? {
+            if !module_dir.is_dir() {
+                continue;
+            }
+
+            let module = Self::file_name(&module_dir)?;
+            migrations.extend(Self::load_module(&module, &module_dir)?);
+        }
+
+        tracing::debug!("Loaded {} migration(s) from {}", migrations.len(), root.display());
+        Ok(migrations)
+    }
+
+    /// Load the migrations of a single module directory.
+    pub fn load_module(module: &str, dir: &Path) -> RepositoryResult<Vec<Migration>> {
+        let mut files: BTreeMap<i32, (String, Option<String>, Option<String>)> = BTreeMap::new();
+
+        for path in Self::sorted_entries(dir)? {
+            if !path.is_file() {
+                continue;
+            }
+
+            let file_name = Self::file_name(&path)?;
+            let Some(parsed) = MigrationFileName::parse(&file_name)
+                .map_err(RepositoryError::ValidationError)?
+            else {
+                continue;
+            };
+
+            let sql = fs::read_to_string(&path).map_err(|e| {
+                RepositoryError::InternalError(format!(
+                    "Failed to read migration {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+            let entry = files
+                .entry(parsed.version)
+                .or_insert_with(|| (parsed.name.clone(), None, None));
+
+            if entry.0 != parsed.name {
+                return Err(RepositoryError::ValidationError(format!(
+                    "Duplicate version {} in module '{}': '{}' and '{}'",
+                    parsed.version, module, entry.0, parsed.name
+                )));
+            }
+
+            let slot = match parsed.direction {
+                MigrationDirection::Up => &mut entry.1,
+                MigrationDirection::Down => &mut entry.2,
+            };
+            *slot = Some(sql);
+        }
+
+        files
+            .into_iter()
+            .map(|(version, (name, up, down))| {
+                let up = up.ok_or_else(|| {
+                    RepositoryError::ValidationError(format!(
+                        "Migration {}:{}_{} has a .down.sql file but no .up.sql",
+                        module, version, name
+                    ))
+                })?;
+                Ok(Migration::owned(module, version, name, up, down))
+            })
+            .collect()
+    }
+
+    fn sorted_entries(dir: &Path) -> RepositoryResult<Vec<std::path::PathBuf>> {
+        let entries = fs::read_dir(dir).map_err(|e| {
+            RepositoryError::InternalError(format!(
+                "Failed to read migrations directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        let mut paths = entries
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| RepositoryError::InternalError(e.to_string()))?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn file_name(path: &Path) -> RepositoryResult<String> {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                RepositoryError::ValidationError(format!(
+                    "Invalid migration path: {}",
+                    path.display()
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/migrations");
+
+    #[test]
+    fn test_parse_file_name() {
+        let parsed = MigrationFileName::parse("2_add_index.down.sql").unwrap().unwrap();
+        assert_eq!(parsed.version, 2);
+        assert_eq!(parsed.name, "add_index");
+        assert_eq!(parsed.direction, MigrationDirection::Down);
+
+        assert!(MigrationFileName::parse("README.md").unwrap().is_none());
+        assert!(MigrationFileName::parse("create_users.up.sql").is_err());
+        assert!(MigrationFileName::parse("1_create_users.sql").is_err());
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let migrations = MigrationLoader::load_dir(FIXTURES).unwrap();
+
+        assert_eq!(migrations.len(), 3);
+        assert_eq!(migrations[0].id(), "inventory:version_1");
+        assert_eq!(migrations[1].id(), "widgets:version_1");
+        assert_eq!(migrations[2].id(), "widgets:version_2");
+        assert_eq!(migrations[1].name, "create_widgets");
+        assert!(migrations[1].sql.contains("CREATE TABLE widgets"));
+        assert!(migrations[1].down_sql.is_some());
+        assert!(migrations[2].down_sql.is_none());
+    }
+
+    #[test]
+    fn test_embedded_matches_loaded() {
+        let embedded: &[Migration] = crate::embed_migrations!("fixtures/migrations");
+        let loaded = MigrationLoader::load_dir(FIXTURES).unwrap();
+
+        assert_eq!(embedded.len(), loaded.len());
+        for (a, b) in embedded.iter().zip(&loaded) {
+            assert_eq!(a.id(), b.id());
+            assert_eq!(a.checksum(), b.checksum());
+            assert_eq!(a.down_sql, b.down_sql);
+        }
+    }
+}
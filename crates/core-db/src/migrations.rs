@@ -1,13 +1,34 @@
-use sqlx::PgPool;
+use async_trait::async_trait;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt;
 use pkg::{RepositoryError, RepositoryResult};
 
-#[derive(Debug, Clone, Copy)]
+/// A migration step written in Rust, for changes that need logic rather than
+/// plain SQL (backfills, re-hashing, batched updates).
+///
+/// The step runs inside a transaction that also records it in
+/// `_schema_migrations`, so a failure leaves nothing behind.
+#[async_trait]
+pub trait CodeMigration: Send + Sync {
+    /// Declared version of the step's logic. It takes the place of the SQL in
+    /// the checksum, so bump it whenever `up` changes meaningfully.
+    fn version(&self) -> &'static str;
+
+    async fn up(&self, tx: &mut Transaction<'_, Postgres>) -> RepositoryResult<()>;
+}
+
+#[derive(Clone)]
 pub struct Migration {
-    pub module: &'static str,
+    pub module: Cow<'static, str>,
     pub version: i32,
-    pub name: &'static str,
-    pub sql: &'static str,
+    pub name: Cow<'static, str>,
+    pub sql: Cow<'static, str>,
+    pub down_sql: Option<Cow<'static, str>>,
+    pub code: Option<&'static dyn CodeMigration>,
 }
 
 impl Migration {
@@ -18,35 +39,159 @@ impl Migration {
         sql: &'static str,
     ) -> Self {
         Self {
-            module,
+            module: Cow::Borrowed(module),
             version,
-            name,
-            sql,
+            name: Cow::Borrowed(name),
+            sql: Cow::Borrowed(sql),
+            down_sql: None,
+            code: None,
         }
     }
 
+    /// A migration implemented in Rust instead of SQL.
+    pub const fn code(
+        module: &'static str,
+        version: i32,
+        name: &'static str,
+        step: &'static dyn CodeMigration,
+    ) -> Self {
+        Self {
+            module: Cow::Borrowed(module),
+            version,
+            name: Cow::Borrowed(name),
+            sql: Cow::Borrowed(""),
+            down_sql: None,
+            code: Some(step),
+        }
+    }
+
+    /// Same as [`Migration::new`], with the SQL that reverts it.
+    pub const fn reversible(
+        module: &'static str,
+        version: i32,
+        name: &'static str,
+        sql: &'static str,
+        down_sql: &'static str,
+    ) -> Self {
+        Self {
+            module: Cow::Borrowed(module),
+            version,
+            name: Cow::Borrowed(name),
+            sql: Cow::Borrowed(sql),
+            down_sql: Some(Cow::Borrowed(down_sql)),
+            code: None,
+        }
+    }
+
+    /// Build a migration from strings known only at runtime, e.g. read from disk.
+    pub fn owned(
+        module: impl Into<String>,
+        version: i32,
+        name: impl Into<String>,
+        sql: impl Into<String>,
+        down_sql: Option<String>,
+    ) -> Self {
+        Self {
+            module: Cow::Owned(module.into()),
+            version,
+            name: Cow::Owned(name.into()),
+            sql: Cow::Owned(sql.into()),
+            down_sql: down_sql.map(Cow::Owned),
+            code: None,
+        }
+    }
+
+    pub fn is_code(&self) -> bool {
+        self.code.is_some()
+    }
+
+    /// SHA-256 of the SQL, in hex; `code-<version>` for code migrations.
     pub fn checksum(&self) -> String {
-        
-        
+        if let Some(step) = self.code {
+            return format!("code-{}", step.version());
+        }
+
+        format!("{:x}", Sha256::digest(self.sql.as_bytes()))
+    }
+
+    /// Checksum recorded by earlier releases: the SQL's length and its first
+    /// and last characters. Still accepted for rows recorded in that format,
+    /// which `run_migrations` upgrades to [`Migration::checksum`].
+    pub fn legacy_checksum(&self) -> String {
+        if self.code.is_some() {
+            return self.checksum();
+        }
+
         let len = self.sql.len();
         let first = self.sql.chars().next().unwrap_or('0');
         let last = self.sql.chars().last().unwrap_or('0');
         format!("{}-{}-{}", len, first as u32, last as u32)
     }
 
+    /// Whether `recorded` is this migration's checksum, in either format.
+    pub fn matches_checksum(&self, recorded: &str) -> bool {
+        recorded == self.checksum() || recorded == self.legacy_checksum()
+    }
+
     pub fn id(&self) -> String {
         format!("{}:version_{}", self.module, self.version)
     }
+
+    fn key(&self) -> String {
+        applied_key(&self.module, self.version)
+    }
 }
 
-#[derive(Debug)]
+impl fmt::Debug for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migration")
+            .field("module", &self.module)
+            .field("version", &self.version)
+            .field("name", &self.name)
+            .field("sql", &self.sql)
+            .field("down_sql", &self.down_sql)
+            .field("code", &self.code.map(|step| step.version()))
+            .finish()
+    }
+}
+
+fn applied_key(module: &str, version: i32) -> String {
+    format!("{}:v{}", module, version)
+}
+
+#[derive(Debug, Clone)]
 struct AppliedMigration {
     module: String,
     version: i32,
     name: String,
     checksum: String,
+    applied_at: String,
+    execution_time_ms: Option<i32>,
+    baselined: bool,
 }
 
+const MIGRATIONS_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS _schema_migrations (
+    id SERIAL PRIMARY KEY,
+    module VARCHAR(100) NOT NULL,
+    version INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    checksum VARCHAR(255) NOT NULL,
+    applied_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    execution_time_ms INTEGER,
+    UNIQUE(module, version)
+);
+
+ALTER TABLE _schema_migrations
+    ADD COLUMN IF NOT EXISTS baselined BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_schema_migrations_module
+    ON _schema_migrations(module);
+
+CREATE INDEX IF NOT EXISTS idx_schema_migrations_applied_at
+    ON _schema_migrations(applied_at);
+"#;
+
 pub struct MigrationRunner {
     pool: PgPool,
 }
@@ -57,26 +202,7 @@ impl MigrationRunner {
     }
 
     async fn ensure_migrations_table(&self) -> RepositoryResult<()> {
-        let sql = r#"
-        CREATE TABLE IF NOT EXISTS _schema_migrations (
-            id SERIAL PRIMARY KEY,
-            module VARCHAR(100) NOT NULL,
-            version INTEGER NOT NULL,
-            name VARCHAR(255) NOT NULL,
-            checksum VARCHAR(255) NOT NULL,
-            applied_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-            execution_time_ms INTEGER,
-            UNIQUE(module, version)
-        );
-        
-        CREATE INDEX IF NOT EXISTS idx_schema_migrations_module 
-            ON _schema_migrations(module);
-        
-        CREATE INDEX IF NOT EXISTS idx_schema_migrations_applied_at 
-            ON _schema_migrations(applied_at);
-        "#;
-
-        sqlx::raw_sql(sql)
+        sqlx::raw_sql(MIGRATIONS_TABLE_SQL)
             .execute(&self.pool)
             .await
             .map_err(|e| {
@@ -90,8 +216,14 @@ impl MigrationRunner {
     }
 
     async fn get_applied_migrations(&self) -> RepositoryResult<HashMap<String, AppliedMigration>> {
-        let records = sqlx::query_as::<_, (String, i32, String, String)>(
-            "SELECT module, version, name, checksum FROM _schema_migrations ORDER BY module, version"
+        let records = sqlx::query_as::<_, (String, i32, String, String, String, Option<i32>, bool)>(
+            r#"
+            SELECT module, version, name, checksum,
+                   to_char(applied_at, 'YYYY-MM-DD HH24:MI:SS') as applied_at,
+                   execution_time_ms, baselined
+            FROM _schema_migrations
+            ORDER BY module, version
+            "#
         )
         .fetch_all(&self.pool)
         .await
@@ -102,15 +234,17 @@ impl MigrationRunner {
         })?;
 
         let mut migrations = HashMap::new();
-        for (module, version, name, checksum) in records {
-            let key = format!("{}:v{}", module, version);
+        for (module, version, name, checksum, applied_at, execution_time_ms, baselined) in records {
             migrations.insert(
-                key,
+                applied_key(&module, version),
                 AppliedMigration {
                     module,
                     version,
                     name,
                     checksum,
+                    applied_at,
+                    execution_time_ms,
+                    baselined,
                 },
             );
         }
@@ -122,7 +256,7 @@ impl MigrationRunner {
         let count: (i64,) = sqlx::query_as(
             "SELECT COUNT(*) FROM _schema_migrations WHERE module = $1 AND version = $2"
         )
-        .bind(migration.module)
+        .bind(migration.module.as_ref())
         .bind(migration.version)
         .fetch_one(&self.pool)
         .await
@@ -135,23 +269,26 @@ impl MigrationRunner {
         Ok(count.0 > 0)
     }
 
-    async fn record_migration(
-        &self,
+    async fn record_migration<'e, E>(
+        executor: E,
         migration: &Migration,
         execution_time_ms: i32,
-    ) -> RepositoryResult<()> {
+    ) -> RepositoryResult<()>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
         sqlx::query(
             r#"
             INSERT INTO _schema_migrations (module, version, name, checksum, execution_time_ms)
             VALUES ($1, $2, $3, $4, $5)
             "#
         )
-        .bind(migration.module)
+        .bind(migration.module.as_ref())
         .bind(migration.version)
-        .bind(migration.name)
+        .bind(migration.name.as_ref())
         .bind(migration.checksum())
         .bind(execution_time_ms)
-        .execute(&self.pool)
+        .execute(executor)
         .await
         .map_err(|e| {
             RepositoryError::DatabaseError(
@@ -162,7 +299,7 @@ impl MigrationRunner {
         Ok(())
     }
 
-    async fn run_migration(&self, migration: &Migration) -> RepositoryResult<i32> {
+    async fn apply_migration(&self, migration: &Migration) -> RepositoryResult<()> {
         let start = std::time::Instant::now();
 
         tracing::info!(
@@ -172,24 +309,88 @@ impl MigrationRunner {
             migration.name
         );
 
-        
-        sqlx::raw_sql(migration.sql)
+        if let Some(step) = migration.code {
+            self.apply_code_migration(migration, step, start).await?;
+        } else {
+            sqlx::raw_sql(&migration.sql)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Migration {} failed: {}", migration.id(), e);
+                    RepositoryError::from(e)
+                })?;
+
+            let execution_time_ms = start.elapsed().as_millis() as i32;
+            Self::record_migration(&self.pool, migration, execution_time_ms).await?;
+        }
+
+        tracing::info!(
+            "    ✓ Completed in {}ms",
+            start.elapsed().as_millis()
+        );
+
+        Ok(())
+    }
+
+    async fn apply_code_migration(
+        &self,
+        migration: &Migration,
+        step: &dyn CodeMigration,
+        start: std::time::Instant,
+    ) -> RepositoryResult<()> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!("Failed to start transaction for {}: {}", migration.id(), e);
+            RepositoryError::from(e)
+        })?;
+
+        // The step's error is returned as-is so its classification and
+        // source survive; the migration it belongs to goes to the log.
+        step.up(&mut tx).await.inspect_err(|e| {
+            tracing::error!("Migration {} failed: {}", migration.id(), e);
+        })?;
+
+        let execution_time_ms = start.elapsed().as_millis() as i32;
+        Self::record_migration(&mut *tx, migration, execution_time_ms).await?;
+
+        tx.commit().await.map_err(|e| {
+            tracing::error!("Failed to commit migration {}: {}", migration.id(), e);
+            RepositoryError::from(e)
+        })
+    }
+
+    /// Rewrite checksums recorded in the legacy format as SHA-256, for the
+    /// migrations whose SQL still matches them.
+    async fn upgrade_legacy_checksums(
+        &self,
+        migrations: &[Migration],
+        applied: &HashMap<String, AppliedMigration>,
+    ) -> RepositoryResult<()> {
+        for migration in migrations {
+            let Some(record) = applied.get(&migration.key()) else {
+                continue;
+            };
+            let checksum = migration.checksum();
+            if record.checksum == checksum || record.checksum != migration.legacy_checksum() {
+                continue;
+            }
+
+            sqlx::query(
+                "UPDATE _schema_migrations SET checksum = $1 WHERE module = $2 AND version = $3"
+            )
+            .bind(&checksum)
+            .bind(migration.module.as_ref())
+            .bind(migration.version)
             .execute(&self.pool)
             .await
             .map_err(|e| {
                 RepositoryError::DatabaseError(
-                    format!("Migration {} failed: {}", migration.id(), e)
+                    format!("Failed to upgrade checksum of {}: {}", migration.id(), e)
                 )
             })?;
+            tracing::debug!("  ↻ Upgraded checksum of {}", migration.id());
+        }
 
-        let execution_time_ms = start.elapsed().as_millis() as i32;
-
-        tracing::info!(
-            "    ✓ Completed in {}ms",
-            execution_time_ms
-        );
-
-        Ok(execution_time_ms)
+        Ok(())
     }
 
     pub async fn run_migrations(&self, migrations: &[Migration]) -> RepositoryResult<()> {
@@ -198,41 +399,35 @@ impl MigrationRunner {
 
         
         let applied = self.get_applied_migrations().await?;
+        self.upgrade_legacy_checksums(migrations, &applied).await?;
 
         tracing::info!("📦 Starting migration check...");
         tracing::info!("   Found {} previously applied migrations", applied.len());
         tracing::info!("   Checking {} total migrations", migrations.len());
 
         
-        let mut by_module: HashMap<&str, Vec<&Migration>> = HashMap::new();
-        for migration in migrations {
-            by_module
-                .entry(migration.module)
-                .or_insert_with(Vec::new)
-                .push(migration);
-        }
-
         let mut total_applied = 0;
         let mut total_skipped = 0;
+        let mut current_module = None;
 
-        for (module_name, module_migrations) in by_module.iter() {
-            tracing::info!("📂 Module: {}", module_name);
-
-            for migration in module_migrations {
-                let is_applied = self.is_applied(migration).await?;
-
-                if is_applied {
-                    tracing::debug!(
-                        "  ⊘ Skipping (already applied): v{} - {}",
-                        migration.version,
-                        migration.name
-                    );
-                    total_skipped += 1;
-                } else {
-                    let execution_time = self.run_migration(migration).await?;
-                    self.record_migration(migration, execution_time).await?;
-                    total_applied += 1;
-                }
+        for migration in apply_order(migrations) {
+            if current_module != Some(migration.module.as_ref()) {
+                current_module = Some(migration.module.as_ref());
+                tracing::info!("📂 Module: {}", migration.module);
+            }
+
+            let is_applied = self.is_applied(migration).await?;
+
+            if is_applied {
+                tracing::debug!(
+                    "  ⊘ Skipping (already applied): v{} - {}",
+                    migration.version,
+                    migration.name
+                );
+                total_skipped += 1;
+            } else {
+                self.apply_migration(migration).await?;
+                total_applied += 1;
             }
         }
 
@@ -252,7 +447,7 @@ impl MigrationRunner {
     pub async fn get_status(&self) -> RepositoryResult<Vec<MigrationStatus>> {
         self.ensure_migrations_table().await?;
 
-        let records = sqlx::query_as::<_, (String, i32, String, String, i32)>(
+        let records = sqlx::query_as::<_, (String, i32, String, String, Option<i32>)>(
             r#"
             SELECT module, version, name, 
                    to_char(applied_at, 'YYYY-MM-DD HH24:MI:SS') as applied_at,
@@ -284,6 +479,70 @@ impl MigrationRunner {
 
         Ok(statuses)
     }
+
+    /// Compute which migrations `run_migrations` would apply, without running them.
+    pub async fn plan(&self, migrations: &[Migration]) -> RepositoryResult<MigrationPlan> {
+        self.ensure_migrations_table().await?;
+
+        let applied = self.get_applied_migrations().await?;
+        Ok(MigrationPlan::from_applied(migrations, &applied))
+    }
+
+    /// Mark every migration of `module` up to and including `version` as applied
+    /// without executing it, for databases whose schema was created outside the
+    /// runner. Returns the migrations that were recorded.
+    pub async fn baseline(
+        &self,
+        migrations: &[Migration],
+        module: &str,
+        version: i32,
+    ) -> RepositoryResult<Vec<Migration>> {
+        self.ensure_migrations_table().await?;
+
+        let applied = self.get_applied_migrations().await?;
+        let baselined = baseline_candidates(migrations, &applied, module, version)?;
+
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            RepositoryError::DatabaseError(format!("Failed to start baseline transaction: {}", e))
+        })?;
+
+        for migration in &baselined {
+            sqlx::query(
+                r#"
+                INSERT INTO _schema_migrations (module, version, name, checksum, baselined)
+                VALUES ($1, $2, $3, $4, TRUE)
+                "#
+            )
+            .bind(migration.module.as_ref())
+            .bind(migration.version)
+            .bind(migration.name.as_ref())
+            .bind(migration.checksum())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                RepositoryError::DatabaseError(
+                    format!("Failed to baseline migration {}: {}", migration.id(), e)
+                )
+            })?;
+
+            tracing::info!("  ⊙ Baselined: {} - {}", migration.id(), migration.name);
+        }
+
+        tx.commit().await.map_err(|e| {
+            RepositoryError::DatabaseError(format!("Failed to commit baseline: {}", e))
+        })?;
+
+        Ok(baselined)
+    }
+
+    /// Compare the migrations known to the code with `_schema_migrations`,
+    /// reporting one entry per migration found on either side.
+    pub async fn status(&self, migrations: &[Migration]) -> RepositoryResult<Vec<MigrationReport>> {
+        self.ensure_migrations_table().await?;
+
+        let applied = self.get_applied_migrations().await?;
+        Ok(MigrationReport::compare(migrations, &applied))
+    }
 }
 
 #[derive(Debug)]
@@ -292,7 +551,224 @@ pub struct MigrationStatus {
     pub version: i32,
     pub name: String,
     pub applied_at: String,
-    pub execution_time_ms: i32,
+    /// `None` for baselined migrations, which were never executed.
+    pub execution_time_ms: Option<i32>,
+}
+
+fn baseline_candidates(
+    migrations: &[Migration],
+    applied: &HashMap<String, AppliedMigration>,
+    module: &str,
+    version: i32,
+) -> RepositoryResult<Vec<Migration>> {
+    let module_migrations: Vec<&Migration> = migrations
+        .iter()
+        .filter(|migration| migration.module == module)
+        .collect();
+
+    if !module_migrations.iter().any(|migration| migration.version == version) {
+        return Err(RepositoryError::ValidationError(format!(
+            "No migration {} v{} is defined in code",
+            module, version
+        )));
+    }
+
+    let mut candidates: Vec<Migration> = module_migrations
+        .into_iter()
+        .filter(|migration| migration.version <= version)
+        .filter(|migration| !applied.contains_key(&migration.key()))
+        .cloned()
+        .collect();
+    candidates.sort_by_key(|migration| migration.version);
+
+    Ok(candidates)
+}
+
+/// The pending migrations for a database, in the order they would be applied.
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    pub pending: Vec<Migration>,
+}
+
+impl MigrationPlan {
+    fn from_applied(migrations: &[Migration], applied: &HashMap<String, AppliedMigration>) -> Self {
+        let pending = apply_order(migrations)
+            .into_iter()
+            .filter(|migration| !applied.contains_key(&migration.key()))
+            .cloned()
+            .collect();
+
+        Self { pending }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Render the plan as a single SQL script, including the tracking table
+    /// and the `_schema_migrations` inserts, wrapped in one transaction so it
+    /// can be reviewed or applied by hand.
+    ///
+    /// A Rust code migration cannot be rendered, and later migrations may
+    /// depend on it, so the script stops before the first one.
+    pub fn to_sql(&self) -> String {
+        let mut script = String::new();
+        script.push_str(&format!(
+            "-- Migration plan: {} pending migration(s)\n",
+            self.pending.len()
+        ));
+        script.push_str("BEGIN;\n");
+        script.push_str(MIGRATIONS_TABLE_SQL);
+
+        for (index, migration) in self.pending.iter().enumerate() {
+            script.push_str(&format!(
+                "\n-- {} - {}\n",
+                migration.id(),
+                migration.name
+            ));
+
+            if migration.is_code() {
+                script.push_str(&format!(
+                    "-- INCOMPLETE: Rust code migration, cannot be rendered as SQL.\n\
+                     -- This script stops here; {} pending migration(s) from this one on\n\
+                     -- are left out. Run `migrate` to apply them.\n",
+                    self.pending.len() - index,
+                ));
+                break;
+            }
+
+            script.push_str(migration.sql.trim());
+            script.push('\n');
+            script.push_str(&format!(
+                "INSERT INTO _schema_migrations (module, version, name, checksum) \
+                 VALUES ({}, {}, {}, {});\n",
+                sql_literal(&migration.module),
+                migration.version,
+                sql_literal(&migration.name),
+                sql_literal(&migration.checksum()),
+            ));
+        }
+
+        script.push_str("\nCOMMIT;\n");
+        script
+    }
+}
+
+/// The order migrations are applied in: modules in the order they first
+/// appear, so a module can depend on the ones listed before it, and each
+/// module's migrations by version.
+fn apply_order(migrations: &[Migration]) -> Vec<&Migration> {
+    let mut modules: Vec<&str> = Vec::new();
+    for migration in migrations {
+        if !modules.contains(&migration.module.as_ref()) {
+            modules.push(&migration.module);
+        }
+    }
+
+    let mut ordered: Vec<&Migration> = migrations.iter().collect();
+    ordered.sort_by_key(|migration| {
+        let module = modules.iter().position(|module| *module == migration.module);
+        (module, migration.version)
+    });
+    ordered
+}
+
+fn sql_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    /// Recorded in the database with the checksum the code expects.
+    Applied,
+    /// Defined in code but not recorded in the database yet.
+    Pending,
+    /// Recorded, but the SQL in code has changed since it was applied.
+    ChecksumMismatch,
+    /// Recorded in the database but no longer defined in code.
+    MissingInCode,
+}
+
+impl MigrationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::ChecksumMismatch => "checksum_mismatch",
+            MigrationState::MissingInCode => "missing_in_code",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub module: String,
+    pub version: i32,
+    pub name: String,
+    pub state: MigrationState,
+    pub checksum: Option<String>,
+    pub applied_checksum: Option<String>,
+    pub applied_at: Option<String>,
+    pub execution_time_ms: Option<i32>,
+    pub baselined: bool,
+}
+
+impl MigrationReport {
+    fn compare(
+        migrations: &[Migration],
+        applied: &HashMap<String, AppliedMigration>,
+    ) -> Vec<MigrationReport> {
+        let mut reports: Vec<MigrationReport> = migrations
+            .iter()
+            .map(|migration| {
+                let checksum = migration.checksum();
+                let record = applied.get(&migration.key());
+                let state = match record {
+                    None => MigrationState::Pending,
+                    Some(record) if !migration.matches_checksum(&record.checksum) => {
+                        MigrationState::ChecksumMismatch
+                    }
+                    Some(_) => MigrationState::Applied,
+                };
+
+                MigrationReport {
+                    module: migration.module.to_string(),
+                    version: migration.version,
+                    name: migration.name.to_string(),
+                    state,
+                    checksum: Some(checksum),
+                    applied_checksum: record.map(|r| r.checksum.clone()),
+                    applied_at: record.map(|r| r.applied_at.clone()),
+                    execution_time_ms: record.and_then(|r| r.execution_time_ms),
+                    baselined: record.is_some_and(|r| r.baselined),
+                }
+            })
+            .collect();
+
+        let known: std::collections::HashSet<String> =
+            migrations.iter().map(Migration::key).collect();
+
+        reports.extend(
+            applied
+                .iter()
+                .filter(|(key, _)| !known.contains(*key))
+                .map(|(_, record)| MigrationReport {
+                    module: record.module.clone(),
+                    version: record.version,
+                    name: record.name.clone(),
+                    state: MigrationState::MissingInCode,
+                    checksum: None,
+                    applied_checksum: Some(record.checksum.clone()),
+                    applied_at: Some(record.applied_at.clone()),
+                    execution_time_ms: record.execution_time_ms,
+                    baselined: record.baselined,
+                }),
+        );
+
+        reports.sort_by(|a, b| (&a.module, a.version).cmp(&(&b.module, b.version)));
+        reports
+    }
 }
 
 #[cfg(test)]
@@ -313,7 +789,29 @@ mod tests {
 
         
         assert_eq!(checksum1, checksum2);
-        assert!(!checksum1.is_empty());
+        assert_eq!(checksum1.len(), 64);
+    }
+
+    #[test]
+    fn test_edits_keeping_length_and_ends_change_the_checksum() {
+        let original = Migration::new("users", 1, "create", "CREATE TABLE users (a INT);");
+        let edited = Migration::new("users", 1, "create", "CREATE TABLE users (b INT);");
+
+        assert_eq!(original.legacy_checksum(), edited.legacy_checksum());
+        assert_ne!(original.checksum(), edited.checksum());
+    }
+
+    #[test]
+    fn test_legacy_checksums_are_accepted() {
+        let create = Migration::new("users", 1, "create_users", "CREATE TABLE users;");
+        let applied: HashMap<_, _> = [applied(&create, &create.legacy_checksum())]
+            .into_iter()
+            .collect();
+
+        let reports = MigrationReport::compare(std::slice::from_ref(&create), &applied);
+        assert_eq!(reports[0].state, MigrationState::Applied);
+        assert!(create.matches_checksum(&create.checksum()));
+        assert!(!create.matches_checksum("stale"));
     }
 
     #[test]
@@ -325,6 +823,134 @@ mod tests {
         assert_eq!(migration2.id(), "products:version_5");
     }
 
+    fn applied(migration: &Migration, checksum: &str) -> (String, AppliedMigration) {
+        (
+            migration.key(),
+            AppliedMigration {
+                module: migration.module.to_string(),
+                version: migration.version,
+                name: migration.name.to_string(),
+                checksum: checksum.to_string(),
+                applied_at: "2024-01-01 00:00:00".to_string(),
+                execution_time_ms: Some(5),
+                baselined: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_report_states() {
+        let create = Migration::new("users", 1, "create_users", "CREATE TABLE users;");
+        let index = Migration::new("users", 2, "add_index", "CREATE INDEX idx ON users(id);");
+        let pending = Migration::new("users", 3, "add_column", "ALTER TABLE users ADD x INT;");
+        let removed = Migration::new("orders", 1, "create_orders", "CREATE TABLE orders;");
+
+        let applied: HashMap<_, _> = [
+            applied(&create, &create.checksum()),
+            applied(&index, "stale"),
+            applied(&removed, &removed.checksum()),
+        ]
+        .into_iter()
+        .collect();
+
+        let reports = MigrationReport::compare(&[create, index, pending], &applied);
+        let states: Vec<_> = reports
+            .iter()
+            .map(|r| (r.module.as_str(), r.version, r.state))
+            .collect();
+
+        assert_eq!(
+            states,
+            vec![
+                ("orders", 1, MigrationState::MissingInCode),
+                ("users", 1, MigrationState::Applied),
+                ("users", 2, MigrationState::ChecksumMismatch),
+                ("users", 3, MigrationState::Pending),
+            ]
+        );
+        assert!(reports[3].applied_at.is_none());
+    }
+
+    #[test]
+    fn test_plan_orders_pending_and_renders_bookkeeping() {
+        let applied_one = Migration::new("users", 1, "create_users", "CREATE TABLE users;");
+        let pending_two = Migration::new("users", 2, "add_index", "CREATE INDEX idx ON users(id);");
+        let pending_other = Migration::new("orders", 1, "o'brien", "CREATE TABLE orders;");
+
+        let applied: HashMap<_, _> = [applied(&applied_one, &applied_one.checksum())]
+            .into_iter()
+            .collect();
+
+        let plan = MigrationPlan::from_applied(
+            &[pending_two, applied_one, pending_other],
+            &applied,
+        );
+        let ids: Vec<_> = plan.pending.iter().map(Migration::id).collect();
+        assert_eq!(ids, vec!["users:version_2", "orders:version_1"]);
+
+        let sql = plan.to_sql();
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS _schema_migrations"));
+        assert!(sql.contains("CREATE INDEX idx ON users(id);"));
+        assert!(!sql.contains("CREATE TABLE users;"));
+        assert!(sql.contains("VALUES ('orders', 1, 'o''brien', "));
+        assert!(sql.find("CREATE INDEX idx") < sql.find("CREATE TABLE orders;"));
+        assert!(sql.starts_with("-- Migration plan: 2 pending migration(s)\nBEGIN;\n"));
+        assert!(sql.ends_with("COMMIT;\n"));
+    }
+
+    #[test]
+    fn test_baseline_candidates() {
+        let migrations = [
+            Migration::new("users", 1, "create_users", "CREATE TABLE users;"),
+            Migration::new("users", 2, "add_index", "CREATE INDEX idx ON users(id);"),
+            Migration::new("users", 3, "add_column", "ALTER TABLE users ADD x INT;"),
+            Migration::new("orders", 1, "create_orders", "CREATE TABLE orders;"),
+        ];
+        let applied: HashMap<_, _> = [applied(&migrations[0], &migrations[0].checksum())]
+            .into_iter()
+            .collect();
+
+        let candidates = baseline_candidates(&migrations, &applied, "users", 2).unwrap();
+        let ids: Vec<_> = candidates.iter().map(Migration::id).collect();
+        assert_eq!(ids, vec!["users:version_2"]);
+
+        assert!(baseline_candidates(&migrations, &applied, "users", 7).is_err());
+        assert!(baseline_candidates(&migrations, &applied, "billing", 1).is_err());
+    }
+
+    struct BackfillFullName;
+
+    #[async_trait]
+    impl CodeMigration for BackfillFullName {
+        fn version(&self) -> &'static str {
+            "2"
+        }
+
+        async fn up(&self, _tx: &mut Transaction<'_, Postgres>) -> RepositoryResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_code_migration_checksum_follows_declared_version() {
+        const BACKFILL: Migration =
+            Migration::code("users", 2, "backfill_full_name", &BackfillFullName);
+
+        assert!(BACKFILL.is_code());
+        assert_eq!(BACKFILL.id(), "users:version_2");
+        assert_eq!(BACKFILL.checksum(), "code-2");
+
+        let before = Migration::new("users", 1, "create_users", "CREATE TABLE users;");
+        let after = Migration::new("users", 3, "drop_name", "ALTER TABLE users DROP name;");
+        let plan = MigrationPlan { pending: vec![before, BACKFILL, after] };
+        let sql = plan.to_sql();
+        assert!(sql.contains("INCOMPLETE: Rust code migration"));
+        assert!(sql.contains("VALUES ('users', 1"));
+        assert!(!sql.contains("VALUES ('users', 2"));
+        assert!(!sql.contains("DROP name"));
+        assert!(sql.trim_end().ends_with("COMMIT;"));
+    }
+
     #[test]
     fn test_different_sql_different_checksum() {
         let migration1 = Migration::new("users", 1, "test", "CREATE TABLE users;");
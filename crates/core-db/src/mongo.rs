@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use core_config::MongoConfig;
+use mongodb::{
+    options::{Acknowledgment, ClientOptions, ReadConcern, WriteConcern},
+    Client, Database,
+};
+use pkg::{RepositoryError, RepositoryResult};
+
+use crate::factory::DatabaseFactory;
+use crate::retry::retry_with_backoff;
+use crate::unit_of_work::DatabaseService;
+
+impl DatabaseFactory {
+    /// Create a MongoDB client from configuration.
+    ///
+    /// Unless `lazy_connect` is set, the server is pinged (retrying with
+    /// backoff according to `connect_retry`) before the client is returned.
+    pub async fn create_mongo_client(config: &MongoConfig) -> RepositoryResult<Client> {
+        let options = Self::mongo_client_options(config).await?;
+        let client = Client::with_options(options)
+            .map_err(RepositoryError::from)?;
+
+        if !config.lazy_connect {
+            retry_with_backoff("MongoDB", &config.connect_retry, |_| true, |remaining| {
+                let ping = client.health_check();
+                async move {
+                    tokio::time::timeout(remaining, ping).await.unwrap_or_else(|elapsed| {
+                        Err(RepositoryError::Timeout { source: Box::new(elapsed) })
+                    })
+                }
+            })
+            .await?;
+        }
+
+        Ok(client)
+    }
+
+    /// Create a client and return a handle to the configured database.
+    pub async fn create_mongo_database(config: &MongoConfig) -> RepositoryResult<Database> {
+        let client = Self::create_mongo_client(config).await?;
+        Self::mongo_database(&client)
+    }
+
+    /// The database from `mongo.database`, or else the one named in the URI.
+    pub fn mongo_database(client: &Client) -> RepositoryResult<Database> {
+        client.default_database().ok_or_else(|| {
+            RepositoryError::DatabaseError(
+                "No MongoDB database: set mongo.database or name one in the URI".to_string(),
+            )
+        })
+    }
+
+    /// Parse the URI and apply the pool sizing, timeouts and read/write
+    /// concern set in the config on top of it. Settings left unset keep the
+    /// URI's value, or the driver default.
+    pub async fn mongo_client_options(config: &MongoConfig) -> RepositoryResult<ClientOptions> {
+        let mut options = ClientOptions::parse(&config.uri)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Invalid MongoDB URI: {}", e)))?;
+
+        if let Some(database) = &config.database {
+            options.default_database = Some(database.clone());
+        }
+        if let Some(app_name) = &config.app_name {
+            options.app_name = Some(app_name.clone());
+        }
+        if let Some(min_pool_size) = config.min_pool_size {
+            options.min_pool_size = Some(min_pool_size);
+        }
+        if let Some(max_pool_size) = config.max_pool_size {
+            options.max_pool_size = Some(max_pool_size);
+        }
+        if let Some(secs) = config.connect_timeout_secs {
+            options.connect_timeout = Some(Duration::from_secs(secs));
+        }
+        if let Some(secs) = config.server_selection_timeout_secs {
+            options.server_selection_timeout = Some(Duration::from_secs(secs));
+        }
+        if let Some(level) = &config.read_concern {
+            options.read_concern = Some(Self::mongo_read_concern(level));
+        }
+        if let Some(write_concern) = Self::mongo_write_concern(config) {
+            options.write_concern = Some(write_concern);
+        }
+
+        Ok(options)
+    }
+
+    fn mongo_read_concern(level: &str) -> ReadConcern {
+        match level {
+            "local" => ReadConcern::local(),
+            "available" => ReadConcern::available(),
+            "majority" => ReadConcern::majority(),
+            "linearizable" => ReadConcern::linearizable(),
+            "snapshot" => ReadConcern::snapshot(),
+            other => ReadConcern::custom(other.to_string()),
+        }
+    }
+
+    fn mongo_write_concern(config: &MongoConfig) -> Option<WriteConcern> {
+        if config.write_concern.is_none()
+            && config.write_journal.is_none()
+            && config.write_timeout_secs.is_none()
+        {
+            return None;
+        }
+
+        let mut write_concern = WriteConcern::default();
+        write_concern.w = config.write_concern.as_deref().map(|w| match w.parse::<u32>() {
+            Ok(nodes) => Acknowledgment::from(nodes),
+            Err(_) => Acknowledgment::from(w.to_string()),
+        });
+        write_concern.journal = config.write_journal;
+        write_concern.w_timeout = config.write_timeout_secs.map(Duration::from_secs);
+        Some(write_concern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::options::ReadConcernLevel;
+
+    #[tokio::test]
+    async fn test_client_options_apply_config() {
+        let config = MongoConfig {
+            uri: "mongodb://mongo.internal:27018/?maxPoolSize=50".to_string(),
+            database: Some("users".to_string()),
+            max_pool_size: Some(20),
+            connect_timeout_secs: Some(5),
+            app_name: Some("users-api".to_string()),
+            read_concern: Some("majority".to_string()),
+            write_concern: Some("majority".to_string()),
+            write_journal: Some(true),
+            ..MongoConfig::default()
+        };
+
+        let options = DatabaseFactory::mongo_client_options(&config).await.unwrap();
+
+        assert_eq!(options.hosts[0].to_string(), "mongo.internal:27018");
+        assert_eq!(options.default_database.as_deref(), Some("users"));
+        assert_eq!(options.max_pool_size, Some(20));
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(options.app_name.as_deref(), Some("users-api"));
+        assert_eq!(options.read_concern.unwrap().level, ReadConcernLevel::Majority);
+
+        let write_concern = options.write_concern.unwrap();
+        assert_eq!(write_concern.w, Some(Acknowledgment::Majority));
+        assert_eq!(write_concern.journal, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_unset_options_keep_the_uri_values() {
+        let config = MongoConfig {
+            uri: "mongodb://mongo.internal/orders?maxPoolSize=50&connectTimeoutMS=2000".to_string(),
+            ..MongoConfig::default()
+        };
+
+        let options = DatabaseFactory::mongo_client_options(&config).await.unwrap();
+
+        assert_eq!(options.default_database.as_deref(), Some("orders"));
+        assert_eq!(options.max_pool_size, Some(50));
+        assert_eq!(options.connect_timeout, Some(Duration::from_millis(2000)));
+        assert_eq!(options.server_selection_timeout, None);
+    }
+
+    #[tokio::test]
+    async fn test_write_concern_node_count() {
+        let config = MongoConfig {
+            write_concern: Some("2".to_string()),
+            ..MongoConfig::default()
+        };
+
+        let options = DatabaseFactory::mongo_client_options(&config).await.unwrap();
+        assert_eq!(options.write_concern.unwrap().w, Some(Acknowledgment::Nodes(2)));
+    }
+
+    #[tokio::test]
+    async fn test_client_options_reject_invalid_uri() {
+        let config = MongoConfig {
+            uri: "postgres://localhost/app".to_string(),
+            ..MongoConfig::default()
+        };
+
+        assert!(DatabaseFactory::mongo_client_options(&config).await.is_err());
+    }
+}
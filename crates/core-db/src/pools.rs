@@ -0,0 +1,164 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+/// The primary pool plus optional read replicas.
+///
+/// Writes and transactions must use [`DatabasePools::primary`]. Read-only
+/// queries go through [`DatabasePools::read`], which spreads them across the
+/// replicas round-robin and retries on the primary when a replica can't be
+/// reached. Cloning is cheap and shares the round-robin cursor.
+#[derive(Debug, Clone)]
+pub struct DatabasePools {
+    primary: PgPool,
+    replicas: Arc<[PgPool]>,
+    next_replica: Arc<AtomicUsize>,
+}
+
+impl DatabasePools {
+    pub fn new(primary: PgPool, replicas: Vec<PgPool>) -> Self {
+        Self {
+            primary,
+            replicas: replicas.into(),
+            next_replica: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Pools without replicas: every query uses the primary.
+    pub fn single(primary: PgPool) -> Self {
+        Self::new(primary, Vec::new())
+    }
+
+    pub fn primary(&self) -> &PgPool {
+        &self.primary
+    }
+
+    pub fn replicas(&self) -> &[PgPool] {
+        &self.replicas
+    }
+
+    /// The next replica in round-robin order, or the primary if there are none.
+    pub fn reader(&self) -> &PgPool {
+        if self.replicas.is_empty() {
+            return &self.primary;
+        }
+
+        let index = self.next_replica.fetch_add(1, Ordering::Relaxed) % self.replicas.len();
+        &self.replicas[index]
+    }
+
+    /// Run a read-only query on a replica, falling back to the primary if the
+    /// replica is unavailable. Query errors (bad SQL, decoding) are returned
+    /// as-is without a retry.
+    pub async fn read<T, F, Fut>(&self, query: F) -> Result<T, sqlx::Error>
+    where
+        F: Fn(PgPool) -> Fut,
+        Fut: Future<Output = Result<T, sqlx::Error>>,
+    {
+        if self.replicas.is_empty() {
+            return query(self.primary.clone()).await;
+        }
+
+        match query(self.reader().clone()).await {
+            Err(e) if Self::is_unavailable(&e) => {
+                tracing::warn!("Read replica unavailable, falling back to primary: {}", e);
+                query(self.primary.clone()).await
+            }
+            result => result,
+        }
+    }
+
+    fn is_unavailable(error: &sqlx::Error) -> bool {
+        matches!(
+            error,
+            sqlx::Error::Io(_)
+                | sqlx::Error::Tls(_)
+                | sqlx::Error::PoolTimedOut
+                | sqlx::Error::PoolClosed
+                | sqlx::Error::WorkerCrashed
+        )
+    }
+}
+
+impl From<PgPool> for DatabasePools {
+    fn from(primary: PgPool) -> Self {
+        Self::single(primary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::Mutex;
+
+    fn lazy_pool(host: &str) -> PgPool {
+        PgPoolOptions::new()
+            .connect_lazy(&format!("postgres://app@{}/app", host))
+            .unwrap()
+    }
+
+    fn host(pool: &PgPool) -> String {
+        pool.connect_options().get_host().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_reader_round_robins_replicas() {
+        let pools = DatabasePools::new(
+            lazy_pool("primary"),
+            vec![lazy_pool("replica-a"), lazy_pool("replica-b")],
+        );
+
+        let hosts: Vec<_> = (0..4).map(|_| host(pools.reader())).collect();
+        assert_eq!(hosts, vec!["replica-a", "replica-b", "replica-a", "replica-b"]);
+        assert_eq!(host(pools.primary()), "primary");
+    }
+
+    #[tokio::test]
+    async fn test_reader_without_replicas_uses_primary() {
+        let pools = DatabasePools::single(lazy_pool("primary"));
+        assert_eq!(host(pools.reader()), "primary");
+    }
+
+    #[tokio::test]
+    async fn test_read_falls_back_to_primary_when_replica_unavailable() {
+        let pools = DatabasePools::new(lazy_pool("primary"), vec![lazy_pool("replica")]);
+        let attempts = Mutex::new(Vec::new());
+
+        let result = pools
+            .read(|pool| {
+                let host = host(&pool);
+                attempts.lock().unwrap().push(host.clone());
+                async move {
+                    if host == "replica" {
+                        Err(sqlx::Error::PoolTimedOut)
+                    } else {
+                        Ok(host)
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result, "primary");
+        assert_eq!(*attempts.lock().unwrap(), vec!["replica", "primary"]);
+    }
+
+    #[tokio::test]
+    async fn test_read_does_not_retry_query_errors() {
+        let pools = DatabasePools::new(lazy_pool("primary"), vec![lazy_pool("replica")]);
+        let calls = AtomicUsize::new(0);
+
+        let result: Result<(), _> = pools
+            .read(|_| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                async { Err(sqlx::Error::RowNotFound) }
+            })
+            .await;
+
+        assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use core_config::RetryConfig;
+use rand::Rng;
+
+/// Delay schedule for [`retry_with_backoff`]: exponential growth from
+/// `initial_delay_ms`, capped at `max_delay_ms`, with optional jitter.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    config: RetryConfig,
+}
+
+impl Backoff {
+    pub fn new(config: &RetryConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based), without jitter.
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let factor = self.config.multiplier.powi(retry.saturating_sub(1) as i32);
+        let millis = (self.config.initial_delay_ms as f64 * factor)
+            .min(self.config.max_delay_ms as f64);
+        Duration::from_millis(millis as u64)
+    }
+
+    /// Delay before retry number `retry`, randomised by `jitter`.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.base_delay(retry);
+        if self.config.jitter <= 0.0 {
+            return base;
+        }
+
+        let spread = rand::thread_rng().gen_range(-self.config.jitter..=self.config.jitter);
+        base.mul_f64((1.0 + spread).max(0.0))
+    }
+}
+
+/// Run `operation` until it succeeds, `is_retryable` rejects the error, the
+/// attempt limit is reached, or the next wait would pass the deadline. Each
+/// failed attempt is logged; the last error is returned.
+///
+/// `operation` is given the time left until the deadline and should give
+/// up by then, so a single slow attempt cannot overrun it.
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    what: &str,
+    config: &RetryConfig,
+    is_retryable: impl Fn(&E) -> bool,
+    mut operation: F,
+) -> Result<T, E>
+where
+    E: Display,
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let backoff = Backoff::new(config);
+    let deadline = Instant::now() + Duration::from_secs(config.deadline_secs);
+    let mut attempt = 1;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let error = match operation(remaining).await {
+            Ok(value) => {
+                if attempt > 1 {
+                    tracing::info!("Connected to {} after {} attempts", what, attempt);
+                }
+                return Ok(value);
+            }
+            Err(error) => error,
+        };
+
+        if !is_retryable(&error) {
+            tracing::error!("Connecting to {} failed: {}", what, error);
+            return Err(error);
+        }
+        if config.max_attempts != 0 && attempt >= config.max_attempts {
+            tracing::error!("Giving up on {} after {} attempts: {}", what, attempt, error);
+            return Err(error);
+        }
+
+        let delay = backoff.delay(attempt);
+        if Instant::now() + delay > deadline {
+            tracing::error!(
+                "Giving up on {} after {} attempts, deadline of {}s reached: {}",
+                what,
+                attempt,
+                config.deadline_secs,
+                error
+            );
+            return Err(error);
+        }
+
+        tracing::warn!(
+            "Connecting to {} failed (attempt {}): {}; retrying in {}ms",
+            what,
+            attempt,
+            error,
+            delay.as_millis()
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn fast_config() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 0,
+            initial_delay_ms: 1,
+            max_delay_ms: 4,
+            multiplier: 2.0,
+            jitter: 0.0,
+            deadline_secs: 5,
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_exponentially_up_to_the_cap() {
+        let backoff = Backoff::new(&RetryConfig {
+            initial_delay_ms: 100,
+            max_delay_ms: 1000,
+            jitter: 0.0,
+            ..RetryConfig::default()
+        });
+
+        let delays: Vec<_> = (1..=6).map(|n| backoff.delay(n).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let backoff = Backoff::new(&RetryConfig {
+            initial_delay_ms: 1000,
+            jitter: 0.25,
+            ..RetryConfig::default()
+        });
+
+        for _ in 0..100 {
+            let delay = backoff.delay(1).as_millis();
+            assert!((750..=1250).contains(&delay), "delay {} out of range", delay);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let calls = Cell::new(0);
+
+        let result: Result<u32, String> = retry_with_backoff("test", &fast_config(), |_| true, |_| {
+            calls.set(calls.get() + 1);
+            let attempt = calls.get();
+            async move {
+                if attempt < 3 {
+                    Err("not up yet".to_string())
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok(3));
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_max_attempts() {
+        let config = RetryConfig {
+            max_attempts: 2,
+            ..fast_config()
+        };
+        let calls = Cell::new(0);
+
+        let result: Result<(), String> = retry_with_backoff("test", &config, |_| true, |_| {
+            calls.set(calls.get() + 1);
+            async { Err("down".to_string()) }
+        })
+        .await;
+
+        assert_eq!(result, Err("down".to_string()));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_permanent_errors() {
+        let calls = Cell::new(0);
+
+        let result: Result<(), String> =
+            retry_with_backoff("test", &fast_config(), |e: &String| e != "bad url", |_| {
+                calls.set(calls.get() + 1);
+                async { Err("bad url".to_string()) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_at_deadline() {
+        let config = RetryConfig {
+            initial_delay_ms: 2_000,
+            max_delay_ms: 2_000,
+            deadline_secs: 1,
+            ..fast_config()
+        };
+        let calls = Cell::new(0);
+
+        let result: Result<(), String> = retry_with_backoff("test", &config, |_| true, |_| {
+            calls.set(calls.get() + 1);
+            async { Err("down".to_string()) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attempts_get_the_time_left() {
+        let config = RetryConfig {
+            max_attempts: 2,
+            deadline_secs: 10,
+            ..fast_config()
+        };
+        let budgets = std::cell::RefCell::new(Vec::new());
+
+        let _: Result<(), String> = retry_with_backoff("test", &config, |_| true, |remaining| {
+            budgets.borrow_mut().push(remaining);
+            async { Err("down".to_string()) }
+        })
+        .await;
+
+        let budgets = budgets.into_inner();
+        assert!(budgets[0] <= Duration::from_secs(10) && budgets[0] > Duration::from_secs(9));
+        assert!(budgets[1] < budgets[0]);
+    }
+}
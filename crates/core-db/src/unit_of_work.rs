@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 use pkg::RepositoryResult;
 
+use crate::health::PoolStats;
+
 #[async_trait]
 pub trait UnitOfWork: Send + Sync {
     async fn begin(&mut self) -> RepositoryResult<()>;
@@ -14,5 +16,11 @@ pub trait UnitOfWork: Send + Sync {
 pub trait DatabaseService: Send + Sync {
     async fn health_check(&self) -> RepositoryResult<bool>;
 
+    /// Where the backend lives, without credentials.
     fn connection_info(&self) -> String;
+
+    /// Connection pool usage, for backends that pool connections.
+    fn pool_stats(&self) -> Option<PoolStats> {
+        None
+    }
 }
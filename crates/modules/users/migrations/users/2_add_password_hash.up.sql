@@ -0,0 +1,3 @@
+
+-- Argon2id PHC string; NULL for accounts without password login
+ALTER TABLE users ADD COLUMN IF NOT EXISTS password_hash TEXT;
@@ -0,0 +1,4 @@
+ALTER TABLE users DROP COLUMN IF EXISTS verification_expires_at;
+ALTER TABLE users DROP COLUMN IF EXISTS verification_token_hash;
+ALTER TABLE users DROP COLUMN IF EXISTS email_verified_at;
+ALTER TABLE users DROP COLUMN IF EXISTS status;
@@ -0,0 +1,7 @@
+
+-- Account lifecycle; accounts created before this migration stay active
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'active'
+    CHECK (status IN ('pending_verification', 'active', 'suspended', 'deactivated'));
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS verification_token_hash CHAR(64);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS verification_expires_at TIMESTAMP WITH TIME ZONE;
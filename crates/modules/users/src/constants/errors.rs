@@ -1,28 +1,232 @@
+use core_auth::AuthError;
+use pkg::{ErrorCode, ProblemCode, RepositoryError};
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::domain::UserStatus;
+
+/// Errors returned by the users service. Storage failures that have no
+/// meaning in the user domain are kept as [`UserError::Repository`].
 #[derive(Error, Debug)]
 pub enum UserError {
     #[error("User with ID {0} not found")]
-    NotFound(Uuid),
+    UserNotFound(Uuid),
+
+    #[error("User '{0}' not found")]
+    UsernameNotFound(String),
 
     #[error("User with ID {0} already exists")]
     AlreadyExists(Uuid),
 
+    #[error("Username '{0}' is already taken")]
+    UsernameTaken(String),
+
+    #[error("Email '{0}' is already registered")]
+    EmailTaken(String),
+
+    #[error("Age {0} is outside the allowed range (1-150)")]
+    InvalidAge(i32),
+
+    #[error("Minimum age {min} cannot be greater than maximum age {max}")]
+    InvalidAgeRange { min: i32, max: i32 },
+
     #[error("Validation error: {0}")]
     ValidationError(String),
 
-    #[error("Database error: {0}")]
-    DatabaseError(String),
+    #[error("Invalid username or password")]
+    InvalidCredentials,
+
+    #[error("{0}")]
+    InvalidSession(String),
+
+    #[error("Password must be at least 8 characters with upper and lower case letters and a digit")]
+    WeakPassword,
+
+    #[error("Account is {0}")]
+    AccountInactive(UserStatus),
+
+    #[error("Cannot change account status from {from} to {to}")]
+    InvalidStatusTransition { from: UserStatus, to: UserStatus },
+
+    #[error("Invalid or expired verification token")]
+    InvalidVerificationToken,
 
-    #[error("Internal error: {0}")]
-    InternalError(String),
+    #[error("Invalid or expired password reset token")]
+    InvalidResetToken,
+
+    #[error(transparent)]
+    Repository(RepositoryError),
 }
 
 pub type UserResult<T> = Result<T, UserError>;
 
+/// Problem codes of the users API: the user-domain codes, plus the shared
+/// [`ErrorCode`]s for everything else. Never rename a code: clients match on
+/// the serialized value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserErrorCode {
+    UserNotFound,
+    UserAlreadyExists,
+    UsernameTaken,
+    EmailTaken,
+    InvalidAge,
+    InvalidCredentials,
+    WeakPassword,
+    AccountInactive,
+    InvalidToken,
+    Common(ErrorCode),
+}
+
+impl ProblemCode for UserErrorCode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserErrorCode::UserNotFound => "USER_NOT_FOUND",
+            UserErrorCode::UserAlreadyExists => "USER_ALREADY_EXISTS",
+            UserErrorCode::UsernameTaken => "USERNAME_TAKEN",
+            UserErrorCode::EmailTaken => "EMAIL_TAKEN",
+            UserErrorCode::InvalidAge => "INVALID_AGE",
+            UserErrorCode::InvalidCredentials => "INVALID_CREDENTIALS",
+            UserErrorCode::WeakPassword => "WEAK_PASSWORD",
+            UserErrorCode::AccountInactive => "ACCOUNT_INACTIVE",
+            UserErrorCode::InvalidToken => "INVALID_TOKEN",
+            UserErrorCode::Common(code) => code.as_str(),
+        }
+    }
+
+    fn status(&self) -> u16 {
+        match self {
+            UserErrorCode::UserNotFound => 404,
+            UserErrorCode::UserAlreadyExists
+            | UserErrorCode::UsernameTaken
+            | UserErrorCode::EmailTaken => 409,
+            UserErrorCode::InvalidAge | UserErrorCode::WeakPassword => 422,
+            UserErrorCode::InvalidToken => 400,
+            UserErrorCode::InvalidCredentials => 401,
+            UserErrorCode::AccountInactive => 403,
+            UserErrorCode::Common(code) => code.status(),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            UserErrorCode::UserNotFound => "User not found",
+            UserErrorCode::UserAlreadyExists => "User already exists",
+            UserErrorCode::UsernameTaken => "Username already taken",
+            UserErrorCode::EmailTaken => "Email already registered",
+            UserErrorCode::InvalidAge => "Invalid age",
+            UserErrorCode::InvalidCredentials => "Invalid credentials",
+            UserErrorCode::WeakPassword => "Password too weak",
+            UserErrorCode::AccountInactive => "Account is not active",
+            UserErrorCode::InvalidToken => "Invalid or expired token",
+            UserErrorCode::Common(code) => code.title(),
+        }
+    }
+}
+
+impl From<ErrorCode> for UserErrorCode {
+    fn from(code: ErrorCode) -> Self {
+        UserErrorCode::Common(code)
+    }
+}
+
+impl UserError {
+    pub fn code(&self) -> UserErrorCode {
+        match self {
+            UserError::UserNotFound(_) | UserError::UsernameNotFound(_) => UserErrorCode::UserNotFound,
+            UserError::AlreadyExists(_) => UserErrorCode::UserAlreadyExists,
+            UserError::UsernameTaken(_) => UserErrorCode::UsernameTaken,
+            UserError::EmailTaken(_) => UserErrorCode::EmailTaken,
+            UserError::InvalidAge(_) | UserError::InvalidAgeRange { .. } => UserErrorCode::InvalidAge,
+            UserError::ValidationError(_) => ErrorCode::ValidationFailed.into(),
+            UserError::InvalidCredentials => UserErrorCode::InvalidCredentials,
+            UserError::InvalidSession(_) => ErrorCode::Unauthorized.into(),
+            UserError::WeakPassword => UserErrorCode::WeakPassword,
+            UserError::AccountInactive(_) => UserErrorCode::AccountInactive,
+            UserError::InvalidStatusTransition { .. } => ErrorCode::Conflict.into(),
+            UserError::InvalidVerificationToken | UserError::InvalidResetToken => {
+                UserErrorCode::InvalidToken
+            }
+            UserError::Repository(e) => e.code().into(),
+        }
+    }
+
+    /// Convert the failure of an insert or update, attributing unique
+    /// violations to the username or email being written.
+    pub fn from_write(error: RepositoryError, username: &str, email: &str) -> Self {
+        match error.constraint() {
+            Some(constraint) if constraint.contains("username") => {
+                UserError::UsernameTaken(username.to_string())
+            }
+            Some(constraint) if constraint.contains("email") => {
+                UserError::EmailTaken(email.to_string())
+            }
+            _ => error.into(),
+        }
+    }
+}
+
+impl From<RepositoryError> for UserError {
+    fn from(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::NotFound(id) => UserError::UserNotFound(id),
+            RepositoryError::AlreadyExists(id) => UserError::AlreadyExists(id),
+            RepositoryError::ValidationError(msg) => UserError::ValidationError(msg),
+            other => UserError::Repository(other),
+        }
+    }
+}
+
+impl From<AuthError> for UserError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::Configuration(msg) => RepositoryError::InternalError(msg).into(),
+            AuthError::Expired => UserError::InvalidSession("Refresh token has expired".to_string()),
+            _ => UserError::InvalidSession("Invalid refresh token".to_string()),
+        }
+    }
+}
+
 impl From<String> for UserError {
     fn from(s: String) -> Self {
         UserError::ValidationError(s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_repository_error() {
+        let id = Uuid::new_v4();
+        assert!(matches!(
+            UserError::from(RepositoryError::NotFound(id)),
+            UserError::UserNotFound(found) if found == id
+        ));
+        assert!(matches!(
+            UserError::from(RepositoryError::DatabaseError("boom".into())),
+            UserError::Repository(_)
+        ));
+    }
+
+    #[test]
+    fn test_unique_violations_are_attributed() {
+        let violation = |constraint: &str| RepositoryError::UniqueViolation {
+            constraint: Some(constraint.to_string()),
+            source: "duplicate key".into(),
+        };
+
+        assert!(matches!(
+            UserError::from_write(violation("users_username_key"), "jane", "jane@example.com"),
+            UserError::UsernameTaken(name) if name == "jane"
+        ));
+        assert!(matches!(
+            UserError::from_write(violation("email_1"), "jane", "jane@example.com"),
+            UserError::EmailTaken(_)
+        ));
+        assert_eq!(
+            UserError::from_write(violation("users_pkey"), "jane", "jane@example.com").code(),
+            UserErrorCode::Common(ErrorCode::Conflict)
+        );
+    }
+}
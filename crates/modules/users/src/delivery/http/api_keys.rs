@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use core_auth::Principal;
+use crate::delivery::http::dto::{ApiResponse, CreateApiKeyDto};
+use crate::domain::ApiKeyScope;
+use crate::repositories::ApiKeyRepository;
+use crate::service::ApiKeyService;
+use super::extract::{Json, Path};
+use super::handler::AppError;
+
+pub struct HttpApiKeyHandler<R: ApiKeyRepository> {
+    api_keys: Arc<ApiKeyService<R>>,
+}
+
+impl<R: ApiKeyRepository> HttpApiKeyHandler<R> {
+    pub fn new(api_keys: Arc<ApiKeyService<R>>) -> Self {
+        Self { api_keys }
+    }
+}
+
+/// `POST /api/api-keys`: issue a key (admin only). The key is in the
+/// response and cannot be shown again.
+pub async fn create_api_key<R: ApiKeyRepository>(
+    State(handler): State<Arc<HttpApiKeyHandler<R>>>,
+    principal: Principal,
+    Json(dto): Json<CreateApiKeyDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+
+    let scope = dto.scope.unwrap_or(ApiKeyScope::ReadOnly);
+    let (api_key, key) = handler.api_keys.create(&principal, &dto.name, scope).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(serde_json::json!({
+            "api_key": api_key,
+            "key": key,
+        }))),
+    ))
+}
+
+/// `GET /api/api-keys`: every key, revoked ones included (admin only).
+pub async fn list_api_keys<R: ApiKeyRepository>(
+    State(handler): State<Arc<HttpApiKeyHandler<R>>>,
+    principal: Principal,
+) -> Result<impl IntoResponse, AppError> {
+    let keys = handler.api_keys.list(&principal).await?;
+    Ok(Json(ApiResponse::success(keys)))
+}
+
+/// `DELETE /api/api-keys/:id`: revoke a key (admin only).
+pub async fn revoke_api_key<R: ApiKeyRepository>(
+    State(handler): State<Arc<HttpApiKeyHandler<R>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let revoked = handler.api_keys.revoke(&principal, id).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "id": id.to_string(),
+        "revoked": revoked,
+    }))))
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use core_auth::Principal;
+use crate::delivery::http::dto::{ApiResponse, LoginDto, PasswordResetConfirmDto, PasswordResetRequestDto, RefreshTokenDto};
+use crate::repositories::{PasswordResetRepository, SessionRepository, UserRepository};
+use crate::service::{PasswordResetService, SessionService, UserService};
+use super::extract::{Json, Path};
+use super::handler::AppError;
+
+pub struct HttpAuthHandler<R: UserRepository, S: SessionRepository> {
+    users: Arc<UserService<R>>,
+    sessions: Arc<SessionService<S>>,
+}
+
+impl<R: UserRepository, S: SessionRepository> HttpAuthHandler<R, S> {
+    pub fn new(users: Arc<UserService<R>>, sessions: Arc<SessionService<S>>) -> Self {
+        Self { users, sessions }
+    }
+}
+
+/// `POST /api/auth/login`: exchange a username and password for tokens.
+pub async fn login<R: UserRepository, S: SessionRepository>(
+    State(handler): State<Arc<HttpAuthHandler<R, S>>>,
+    Json(dto): Json<LoginDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+
+    let user = handler.users.authenticate(&dto.username, &dto.password).await?;
+    let tokens = handler.sessions.start(&user).await?;
+
+    tracing::info!(user_id = %user.id, "User logged in");
+    Ok(Json(ApiResponse::success(tokens)))
+}
+
+/// `POST /api/auth/refresh`: rotate a refresh token.
+pub async fn refresh<R: UserRepository, S: SessionRepository>(
+    State(handler): State<Arc<HttpAuthHandler<R, S>>>,
+    Json(dto): Json<RefreshTokenDto>,
+) -> Result<impl IntoResponse, AppError> {
+    // Suspending or deactivating an account ends its sessions on next use.
+    let user_id = handler.sessions.user_of(&dto.refresh_token).await?;
+    if let Err(e) = handler.users.ensure_can_sign_in(user_id).await {
+        handler.sessions.logout(&dto.refresh_token).await?;
+        return Err(e.into());
+    }
+
+    let tokens = handler.sessions.refresh(&dto.refresh_token).await?;
+    Ok(Json(ApiResponse::success(tokens)))
+}
+
+/// `POST /api/auth/logout`: end the session of a refresh token.
+pub async fn logout<R: UserRepository, S: SessionRepository>(
+    State(handler): State<Arc<HttpAuthHandler<R, S>>>,
+    Json(dto): Json<RefreshTokenDto>,
+) -> Result<impl IntoResponse, AppError> {
+    handler.sessions.logout(&dto.refresh_token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `DELETE /api/users/:id/sessions`: sign a user out everywhere (admin only).
+pub async fn revoke_sessions<R: UserRepository, S: SessionRepository>(
+    State(handler): State<Arc<HttpAuthHandler<R, S>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let revoked = handler.sessions.revoke_all(&principal, id).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "user_id": id.to_string(),
+        "revoked": revoked,
+    }))))
+}
+
+pub struct HttpPasswordResetHandler<R: UserRepository, P: PasswordResetRepository, S: SessionRepository> {
+    resets: Arc<PasswordResetService<R, P, S>>,
+}
+
+impl<R, P, S> HttpPasswordResetHandler<R, P, S>
+where
+    R: UserRepository,
+    P: PasswordResetRepository,
+    S: SessionRepository,
+{
+    pub fn new(resets: Arc<PasswordResetService<R, P, S>>) -> Self {
+        Self { resets }
+    }
+}
+
+/// `POST /api/auth/password-reset/request`: mail a reset token.
+///
+/// Always `202 Accepted`, and the work happens after responding, so neither
+/// the status nor the timing reveals whether the address is registered.
+pub async fn request_password_reset<R, P, S>(
+    State(handler): State<Arc<HttpPasswordResetHandler<R, P, S>>>,
+    Json(dto): Json<PasswordResetRequestDto>,
+) -> Result<impl IntoResponse, AppError>
+where
+    R: UserRepository + Send + Sync + 'static,
+    P: PasswordResetRepository + Send + Sync + 'static,
+    S: SessionRepository + Send + Sync + 'static,
+{
+    dto.validate()?;
+
+    let resets = handler.resets.clone();
+    tokio::spawn(async move {
+        if let Err(e) = resets.request(&dto.email).await {
+            tracing::error!("Password reset request failed: {}", e);
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// `POST /api/auth/password-reset/confirm`: set a new password with a reset
+/// token, signing the user out everywhere.
+pub async fn confirm_password_reset<R, P, S>(
+    State(handler): State<Arc<HttpPasswordResetHandler<R, P, S>>>,
+    Json(dto): Json<PasswordResetConfirmDto>,
+) -> Result<impl IntoResponse, AppError>
+where
+    R: UserRepository,
+    P: PasswordResetRepository,
+    S: SessionRepository,
+{
+    dto.validate()?;
+
+    handler.resets.confirm(&dto.token, &dto.new_password).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
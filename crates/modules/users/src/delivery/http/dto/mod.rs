@@ -1,7 +1,12 @@
+use std::fmt;
+
+use pkg::utils::validation::is_strong_password;
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+use crate::domain::{ApiKeyScope, UserStatus};
+
+#[derive(Clone, Serialize, Deserialize, Validate)]
 pub struct CreateUserDto {
     #[validate(length(min = 3, max = 50))]
     pub username: String,
@@ -14,6 +19,10 @@ pub struct CreateUserDto {
     
     #[validate(range(min = 1, max = 150))]
     pub age: Option<i32>,
+
+    #[serde(skip_serializing)]
+    #[validate(custom(function = "validate_password"))]
+    pub password: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -31,6 +40,121 @@ pub struct UpdateUserDto {
     pub age: Option<i32>,
 }
 
+/// Body of `POST /api/users/:id/password`. `current_password` is required
+/// when users change their own password; admins may reset without it.
+#[derive(Clone, Deserialize, Validate)]
+pub struct ChangePasswordDto {
+    pub current_password: Option<String>,
+
+    #[validate(custom(function = "validate_password"))]
+    pub new_password: String,
+}
+
+/// Body of `POST /api/auth/login`.
+#[derive(Clone, Deserialize, Validate)]
+pub struct LoginDto {
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+
+    #[validate(length(min = 1, max = 128))]
+    pub password: String,
+}
+
+/// Body of `POST /api/auth/refresh` and `POST /api/auth/logout`.
+#[derive(Clone, Deserialize)]
+pub struct RefreshTokenDto {
+    pub refresh_token: String,
+}
+
+/// Body of `POST /api/users/:id/verify`.
+#[derive(Clone, Deserialize, Validate)]
+pub struct VerifyEmailDto {
+    #[validate(length(min = 1))]
+    pub token: String,
+}
+
+/// Body of `POST /api/auth/password-reset/request`.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct PasswordResetRequestDto {
+    #[validate(email)]
+    pub email: String,
+}
+
+/// Body of `POST /api/auth/password-reset/confirm`.
+#[derive(Clone, Deserialize, Validate)]
+pub struct PasswordResetConfirmDto {
+    #[validate(length(min = 1))]
+    pub token: String,
+
+    #[validate(custom(function = "validate_password"))]
+    pub new_password: String,
+}
+
+/// Body of `POST /api/api-keys`.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateApiKeyDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// Defaults to read-only.
+    pub scope: Option<ApiKeyScope>,
+}
+
+fn validate_password(password: &str) -> Result<(), ValidationError> {
+    if is_strong_password(password) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("password_strength").with_message(
+            "must be at least 8 characters with upper and lower case letters and a digit".into(),
+        ))
+    }
+}
+
+// Passwords are left out of Debug output so DTOs can't leak them into logs.
+
+impl fmt::Debug for CreateUserDto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateUserDto")
+            .field("username", &self.username)
+            .field("email", &self.email)
+            .field("full_name", &self.full_name)
+            .field("age", &self.age)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for ChangePasswordDto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChangePasswordDto").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for LoginDto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoginDto")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for RefreshTokenDto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefreshTokenDto").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for VerifyEmailDto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifyEmailDto").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for PasswordResetConfirmDto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PasswordResetConfirmDto").finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserResponse {
     pub id: String,
@@ -38,6 +162,7 @@ pub struct UserResponse {
     pub email: String,
     pub full_name: String,
     pub age: Option<i32>,
+    pub status: UserStatus,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -1,17 +1,19 @@
 use std::sync::Arc;
 use axum::{
-    extract::{Path, Query, State},
+    extract::State,
     http::StatusCode,
     response::{IntoResponse, Response},
-    Json,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationErrors};
 
-use pkg::RepositoryError;
+use core_auth::Principal;
+use pkg::{ProblemDetails, RepositoryError};
+use crate::constants::UserError;
 use crate::domain::User;
-use crate::delivery::http::dto::{CreateUserDto, UpdateUserDto, UserResponse, ApiResponse, UserListResponse};
+use crate::delivery::http::extract::{Json, Path, Query};
+use crate::delivery::http::dto::{ChangePasswordDto, CreateUserDto, UpdateUserDto, UserResponse, ApiResponse, UserListResponse, VerifyEmailDto};
 use crate::repositories::UserRepository;
 use crate::service::UserService;
 
@@ -47,88 +49,65 @@ impl From<User> for UserResponse {
             email: user.email,
             full_name: user.full_name,
             age: user.age,
+            status: user.status,
             created_at: user.created_at.to_rfc3339(),
             updated_at: user.updated_at.to_rfc3339(),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-    pub details: Option<Vec<String>>,
+/// Error returned by the user handlers, rendered as an RFC 7807
+/// `application/problem+json` response with a stable [`crate::constants::UserErrorCode`].
+#[derive(Debug)]
+pub enum AppError {
+    User(UserError),
+    Validation(ValidationErrors),
+    Problem(ProblemDetails),
 }
 
-pub struct AppError(pub RepositoryError);
+impl From<UserError> for AppError {
+    fn from(err: UserError) -> Self {
+        AppError::User(err)
+    }
+}
 
 impl From<RepositoryError> for AppError {
     fn from(err: RepositoryError) -> Self {
-        AppError(err)
+        AppError::User(err.into())
     }
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, message, details) = match self.0 {
-            RepositoryError::NotFound(id) => (
-                StatusCode::NOT_FOUND,
-                format!("Resource not found: {}", id),
-                None,
-            ),
-            RepositoryError::AlreadyExists(id) => (
-                StatusCode::CONFLICT,
-                format!("Resource already exists with id: {}", id),
-                None,
-            ),
-            RepositoryError::ValidationError(msg) => (
-                StatusCode::BAD_REQUEST,
-                "Validation failed".to_string(),
-                Some(vec![msg]),
-            ),
-            RepositoryError::DatabaseError(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Database error occurred".to_string(),
-                Some(vec![msg]),
-            ),
-            RepositoryError::InternalError(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal server error".to_string(),
-                Some(vec![msg]),
-            ),
-            RepositoryError::Unauthorized(msg) => (
-                StatusCode::UNAUTHORIZED,
-                "Unauthorized".to_string(),
-                Some(vec![msg]),
-            ),
-            RepositoryError::Forbidden(msg) => (
-                StatusCode::FORBIDDEN,
-                "Forbidden".to_string(),
-                Some(vec![msg]),
-            ),
-            RepositoryError::BadRequest(msg) => (
-                StatusCode::BAD_REQUEST,
-                "Bad request".to_string(),
-                Some(vec![msg]),
-            ),
-        };
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(errors)
+    }
+}
 
-        let body = Json(ErrorResponse {
-            error: message,
-            details,
-        });
+impl AppError {
+    pub fn to_problem(&self) -> ProblemDetails {
+        match self {
+            AppError::Validation(errors) => ProblemDetails::from_validation_errors(errors),
+            AppError::Problem(problem) => problem.clone(),
+            AppError::User(UserError::Repository(err)) => ProblemDetails::from_repository_error(err),
+            AppError::User(err) => ProblemDetails::new(err.code()).with_detail(err.to_string()),
+        }
+    }
+}
 
-        (status, body).into_response()
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        self.to_problem().into_response()
     }
 }
 
 pub async fn create_user<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
     Json(dto): Json<CreateUserDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    dto.validate()
-        .map_err(|e| AppError(RepositoryError::ValidationError(format!("{}", e))))?;
+    dto.validate()?;
 
-    let user = handler.service.create_user(dto).await?;
+    let user = handler.service.create_user(&principal, dto).await?;
     let response = ApiResponse::success(UserResponse::from(user));
     
     Ok((StatusCode::CREATED, Json(response)))
@@ -136,8 +115,9 @@ pub async fn create_user<R: UserRepository>(
 
 pub async fn get_all_users<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
 ) -> Result<impl IntoResponse, AppError> {
-    let users = handler.service.get_all_users().await?;
+    let users = handler.service.get_all_users(&principal).await?;
     let total = users.len();
     
     let user_responses: Vec<UserResponse> = users
@@ -155,9 +135,10 @@ pub async fn get_all_users<R: UserRepository>(
 
 pub async fn get_user<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
-    let user = handler.service.get_user(id).await?;
+    let user = handler.service.get_user(&principal, id).await?;
     let response = ApiResponse::success(UserResponse::from(user));
     
     Ok(Json(response))
@@ -165,23 +146,86 @@ pub async fn get_user<R: UserRepository>(
 
 pub async fn update_user<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
     Json(dto): Json<UpdateUserDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    dto.validate()
-        .map_err(|e| AppError(RepositoryError::ValidationError(format!("{}", e))))?;
+    dto.validate()?;
 
-    let user = handler.service.update_user(id, dto).await?;
+    let user = handler.service.update_user(&principal, id, dto).await?;
     let response = ApiResponse::success(UserResponse::from(user));
     
     Ok(Json(response))
 }
 
+pub async fn change_password<R: UserRepository>(
+    State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<ChangePasswordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+
+    handler.service.change_password(&principal, id, dto).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn verify_email<R: UserRepository>(
+    State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<VerifyEmailDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+
+    let user = handler.service.verify_email(&principal, id, &dto.token).await?;
+    Ok(Json(ApiResponse::success(UserResponse::from(user))))
+}
+
+/// `POST /api/users/:id/verify/resend`: mail a new verification token.
+pub async fn resend_verification<R: UserRepository>(
+    State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    handler.service.resend_verification(&principal, id).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+pub async fn suspend_user<R: UserRepository>(
+    State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = handler.service.suspend_user(&principal, id).await?;
+    Ok(Json(ApiResponse::success(UserResponse::from(user))))
+}
+
+pub async fn reactivate_user<R: UserRepository>(
+    State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = handler.service.reactivate_user(&principal, id).await?;
+    Ok(Json(ApiResponse::success(UserResponse::from(user))))
+}
+
+pub async fn deactivate_user<R: UserRepository>(
+    State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = handler.service.deactivate_user(&principal, id).await?;
+    Ok(Json(ApiResponse::success(UserResponse::from(user))))
+}
+
 pub async fn delete_user<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
-    let deleted = handler.service.delete_user(id).await?;
+    let deleted = handler.service.delete_user(&principal, id).await?;
     
     let response = ApiResponse::success(serde_json::json!({
         "deleted": deleted,
@@ -193,38 +237,38 @@ pub async fn delete_user<R: UserRepository>(
 
 pub async fn find_by_username<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
     Query(query): Query<UsernameQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    query.validate()
-        .map_err(|e| AppError(RepositoryError::ValidationError(format!("{}", e))))?;
+    query.validate()?;
 
-    let user = handler.service.find_by_username(&query.username).await?;
+    let user = handler.service.find_by_username(&principal, &query.username).await?;
     
     match user {
         Some(u) => {
             let response = ApiResponse::success(UserResponse::from(u));
             Ok(Json(response))
         }
-        None => Err(AppError(RepositoryError::ValidationError(
-            format!("User not found with username: {}", query.username)
-        ))),
+        None => Err(UserError::UsernameNotFound(query.username).into()),
     }
 }
 
 pub async fn filter_by_age_range<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
     Query(query): Query<AgeRangeQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    query.validate()
-        .map_err(|e| AppError(RepositoryError::ValidationError(format!("{}", e))))?;
+    query.validate()?;
 
     if query.min_age > query.max_age {
-        return Err(AppError(RepositoryError::ValidationError(
-            "min_age must be less than or equal to max_age".to_string()
-        )));
+        return Err(UserError::InvalidAgeRange {
+            min: query.min_age,
+            max: query.max_age,
+        }
+        .into());
     }
 
-    let users = handler.service.get_users_by_age_range(query.min_age, query.max_age).await?;
+    let users = handler.service.get_users_by_age_range(&principal, query.min_age, query.max_age).await?;
     let total = users.len();
     
     let user_responses: Vec<UserResponse> = users
@@ -242,8 +286,9 @@ pub async fn filter_by_age_range<R: UserRepository>(
 
 pub async fn get_statistics<R: UserRepository>(
     State(handler): State<Arc<HttpUserHandler<R>>>,
+    principal: Principal,
 ) -> Result<impl IntoResponse, AppError> {
-    let stats = handler.service.get_statistics().await?;
+    let stats = handler.service.get_statistics(&principal).await?;
     
     let response = ApiResponse::success(serde_json::json!({
         "total_users": stats.total_users,
@@ -254,10 +299,35 @@ pub async fn get_statistics<R: UserRepository>(
     Ok(Json(response))
 }
 
-pub async fn health_check() -> impl IntoResponse {
-    Json(serde_json::json!({
-        "status": "healthy",
-        "service": "user-service",
-        "timestamp": chrono::Utc::now().to_rfc3339(),
-    }))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_error_codes() {
+        let not_found = AppError::from(RepositoryError::NotFound(Uuid::new_v4())).to_problem();
+        assert_eq!(not_found.code, "USER_NOT_FOUND");
+        assert_eq!(not_found.status, 404);
+
+        let taken = AppError::from(UserError::UsernameTaken("jane".to_string())).to_problem();
+        assert_eq!(taken.code, "USERNAME_TAKEN");
+        assert_eq!(taken.status, 409);
+
+        let age = AppError::from(UserError::InvalidAge(200)).to_problem();
+        assert_eq!(age.status, 422);
+
+        let invalid = CreateUserDto {
+            username: "ab".to_string(),
+            email: "ab@example.com".to_string(),
+            full_name: "A B".to_string(),
+            age: None,
+            password: "Password123".to_string(),
+        };
+        let problem = AppError::from(invalid.validate().unwrap_err()).to_problem();
+        assert_eq!(problem.code, "VALIDATION_FAILED");
+        assert_eq!(problem.errors[0].field, "username");
+
+        let internal = AppError::from(RepositoryError::DatabaseError("syntax error at \"FROM\"".into()));
+        assert!(!internal.to_problem().detail.unwrap().contains("syntax"));
+    }
 }
@@ -1,7 +1,12 @@
+pub mod api_keys;
+pub mod auth;
 pub mod dto;
+pub mod extract;
 pub mod handler;
 pub mod router;
 
+pub use api_keys::*;
+pub use auth::*;
 pub use dto::*;
 pub use handler::*;
 pub use router::*;
@@ -3,14 +3,31 @@ use axum::{
     routing::{get, post, put, delete},
     Router,
 };
-use tower_http::cors::{CorsLayer, Any};
 use tower_http::trace::TraceLayer;
+use core_auth::{rate_limit, require_auth, without_auth, Authenticator, RateLimiter};
 
-use crate::repositories::UserRepository;
-use crate::service::UserService;
+use crate::repositories::{ApiKeyRepository, PasswordResetRepository, SessionRepository, UserRepository};
+use crate::service::{ApiKeyService, PasswordResetService, SessionService, UserService};
+use super::api_keys::{HttpApiKeyHandler, create_api_key, list_api_keys, revoke_api_key};
+use super::auth::{
+    HttpAuthHandler,
+    HttpPasswordResetHandler,
+    confirm_password_reset,
+    login,
+    logout,
+    refresh,
+    request_password_reset,
+    revoke_sessions,
+};
 use super::handler::{
     HttpUserHandler,
+    change_password,
     create_user,
+    deactivate_user,
+    reactivate_user,
+    resend_verification,
+    suspend_user,
+    verify_email,
     get_all_users,
     get_user,
     update_user,
@@ -18,36 +35,486 @@ use super::handler::{
     find_by_username,
     filter_by_age_range,
     get_statistics,
-    health_check,
 };
 
+/// Routes of the users API. With an `authenticator` every route requires a
+/// valid bearer token or API key; `None` leaves them public (auth disabled by
+/// configuration), with every caller treated as [`core_auth::Principal::system`].
+/// The `rate_limiter` runs after authentication, so it can tell callers apart.
 pub fn create_user_router<R: UserRepository + Send + Sync + 'static>(
     service: Arc<UserService<R>>,
+    authenticator: Option<Arc<Authenticator>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 ) -> Router {
     let handler = Arc::new(HttpUserHandler::new(service));
 
-    Router::new()
-        .route("/health", get(health_check))
-        
+    let router = Router::new()
         .route("/api/users", post(create_user::<R>))
         .route("/api/users", get(get_all_users::<R>))
         .route("/api/users/:id", get(get_user::<R>))
         .route("/api/users/:id", put(update_user::<R>))
         .route("/api/users/:id", delete(delete_user::<R>))
+        .route("/api/users/:id/password", post(change_password::<R>))
+        .route("/api/users/:id/verify", post(verify_email::<R>))
+        .route("/api/users/:id/verify/resend", post(resend_verification::<R>))
+        .route("/api/users/:id/suspend", post(suspend_user::<R>))
+        .route("/api/users/:id/reactivate", post(reactivate_user::<R>))
+        .route("/api/users/:id/deactivate", post(deactivate_user::<R>))
         
         .route("/api/users/search/username", get(find_by_username::<R>))
         .route("/api/users/filter/age", get(filter_by_age_range::<R>))
         
         .route("/api/users/statistics", get(get_statistics::<R>))
         
-        .with_state(handler)
-        
-        .layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods(Any)
-                .allow_headers(Any)
-        )
-        
-        .layer(TraceLayer::new_for_http())
+        .with_state(handler);
+
+    let router = with_rate_limit(router, rate_limiter);
+    let router = match authenticator {
+        Some(authenticator) => {
+            router.route_layer(axum::middleware::from_fn_with_state(authenticator, require_auth))
+        }
+        None => router.route_layer(axum::middleware::from_fn(without_auth)),
+    };
+
+    with_http_layers(router)
+}
+
+/// Login, refresh and logout under `/api/auth`, which are public, plus the
+/// admin-only `DELETE /api/users/:id/sessions`.
+pub fn create_auth_router<R, S>(
+    users: Arc<UserService<R>>,
+    sessions: Arc<SessionService<S>>,
+    authenticator: Arc<Authenticator>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Router
+where
+    R: UserRepository + Send + Sync + 'static,
+    S: SessionRepository + Send + Sync + 'static,
+{
+    let handler = Arc::new(HttpAuthHandler::new(users, sessions));
+
+    let protected = Router::new()
+        .route("/api/users/:id/sessions", delete(revoke_sessions::<R, S>))
+        .with_state(handler.clone());
+    let protected = with_rate_limit(protected, rate_limiter.clone())
+        .route_layer(axum::middleware::from_fn_with_state(authenticator, require_auth));
+
+    let router = Router::new()
+        .route("/api/auth/login", post(login::<R, S>))
+        .route("/api/auth/refresh", post(refresh::<R, S>))
+        .route("/api/auth/logout", post(logout::<R, S>))
+        .with_state(handler);
+    let router = with_rate_limit(router, rate_limiter).merge(protected);
+
+    with_http_layers(router)
+}
+
+/// The public password reset routes under `/api/auth/password-reset`.
+pub fn create_password_reset_router<R, P, S>(
+    resets: Arc<PasswordResetService<R, P, S>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Router
+where
+    R: UserRepository + Send + Sync + 'static,
+    P: PasswordResetRepository + Send + Sync + 'static,
+    S: SessionRepository + Send + Sync + 'static,
+{
+    let handler = Arc::new(HttpPasswordResetHandler::new(resets));
+
+    let router = Router::new()
+        .route("/api/auth/password-reset/request", post(request_password_reset::<R, P, S>))
+        .route("/api/auth/password-reset/confirm", post(confirm_password_reset::<R, P, S>))
+        .with_state(handler);
+
+    with_http_layers(with_rate_limit(router, rate_limiter))
+}
+
+/// Admin-only management of API keys under `/api/api-keys`.
+pub fn create_api_key_router<R: ApiKeyRepository + Send + Sync + 'static>(
+    api_keys: Arc<ApiKeyService<R>>,
+    authenticator: Arc<Authenticator>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Router {
+    let handler = Arc::new(HttpApiKeyHandler::new(api_keys));
+
+    let router = Router::new()
+        .route("/api/api-keys", post(create_api_key::<R>))
+        .route("/api/api-keys", get(list_api_keys::<R>))
+        .route("/api/api-keys/:id", delete(revoke_api_key::<R>))
+        .with_state(handler);
+    let router = with_rate_limit(router, rate_limiter)
+        .route_layer(axum::middleware::from_fn_with_state(authenticator, require_auth));
+
+    with_http_layers(router)
+}
+
+fn with_rate_limit(router: Router, rate_limiter: Option<Arc<RateLimiter>>) -> Router {
+    match rate_limiter {
+        Some(limiter) => router.route_layer(axum::middleware::from_fn_with_state(limiter, rate_limit)),
+        None => router,
+    }
+}
+
+/// CORS is left to the application, which applies `server.cors` once to
+/// every router it serves.
+fn with_http_layers(router: Router) -> Router {
+    router.layer(TraceLayer::new_for_http())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::{header, Request, StatusCode}};
+    use core_auth::{JwtVerifier, Principal, TokenIssuer, API_KEY_HEADER};
+    use core_config::AuthConfig;
+    use crate::delivery::http::dto::CreateUserDto;
+    use crate::domain::ApiKeyScope;
+    use crate::service::ApiKeyService;
+    use tower::ServiceExt;
+    use crate::repositories::{
+        InMemoryApiKeyRepository,
+        InMemoryPasswordResetRepository,
+        InMemorySessionRepository,
+        InMemoryUserRepository,
+    };
+
+    fn service() -> Arc<UserService<InMemoryUserRepository>> {
+        Arc::new(UserService::new(Arc::new(InMemoryUserRepository::new())))
+    }
+
+    fn list_users() -> Request<Body> {
+        Request::builder().uri("/api/users").body(Body::empty()).unwrap()
+    }
+
+    fn auth_config() -> AuthConfig {
+        AuthConfig {
+            jwt_secret: Some("test-secret-that-is-at-least-32-bytes".to_string()),
+            ..AuthConfig::default()
+        }
+    }
+
+    fn authenticator() -> Arc<Authenticator> {
+        Arc::new(Authenticator::new(Arc::new(JwtVerifier::from_config(&auth_config()).unwrap())))
+    }
+
+    async fn body_json(response: axum::response::Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_users_routes_require_a_token() {
+        let response = create_user_router(service(), Some(authenticator()), None)
+            .oneshot(list_users())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = create_user_router(service(), None, None)
+            .oneshot(list_users())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_requests_get_problem_responses() {
+        let app = create_user_router(service(), None, None);
+        let requests = [
+            (
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/users")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from("{\"username\":"))
+                    .unwrap(),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/users")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from("{\"username\": 42}"))
+                    .unwrap(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                Request::builder().uri("/api/users/not-a-uuid").body(Body::empty()).unwrap(),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                Request::builder()
+                    .uri("/api/users/filter/age?min_age=ten")
+                    .body(Body::empty())
+                    .unwrap(),
+                StatusCode::BAD_REQUEST,
+            ),
+        ];
+
+        for (request, status) in requests {
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], pkg::PROBLEM_JSON);
+            let body = body_json(response).await;
+            assert_eq!(body["status"], status.as_u16());
+            assert!(body["detail"].is_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_callers_get_429() {
+        let config = core_config::RateLimitConfig {
+            default: core_config::RateLimitRule {
+                key: core_config::RateLimitKey::Principal,
+                burst: 1,
+                per_minute: 1,
+            },
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config, Arc::new(core_auth::InMemoryRateLimitStore::new()));
+        let app = create_user_router(service(), None, Some(Arc::new(limiter)));
+
+        let response = app.clone().oneshot(list_users()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["ratelimit-remaining"], "0");
+
+        let response = app.oneshot(list_users()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        assert_eq!(body_json(response).await["code"], "RATE_LIMITED");
+    }
+
+    #[tokio::test]
+    async fn test_login_refresh_and_logout() {
+        let service = service();
+        let jane = service
+            .create_user(
+                &Principal::system(),
+                CreateUserDto {
+                    username: "jane".to_string(),
+                    email: "jane@example.com".to_string(),
+                    full_name: "Jane Doe".to_string(),
+                    age: None,
+                    password: "Password123".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let verifier = Arc::new(JwtVerifier::from_config(&auth_config()).unwrap());
+        let sessions = Arc::new(SessionService::new(
+            Arc::new(InMemorySessionRepository::new()),
+            Arc::new(TokenIssuer::from_config(&auth_config()).unwrap().unwrap()),
+            verifier.clone(),
+            chrono::Duration::hours(1),
+        ));
+        let app = create_user_router(service.clone(), Some(authenticator()), None)
+            .merge(create_auth_router(service, sessions, authenticator(), None));
+
+        let login = |password: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "username": "jane", "password": password }).to_string(),
+                ))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(login("Password124")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.clone().oneshot(login("Password123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let tokens = body_json(response).await;
+        let access_token = tokens["data"]["access_token"].as_str().unwrap();
+        assert!(tokens["data"]["refresh_token"].is_string());
+
+        let post_json = |uri: &str, body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let refresh_token = tokens["data"]["refresh_token"].as_str().unwrap();
+        let response = app
+            .clone()
+            .oneshot(post_json("/api/auth/refresh", serde_json::json!({ "refresh_token": refresh_token })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let rotated = body_json(response).await;
+        let rotated = rotated["data"]["refresh_token"].as_str().unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(post_json("/api/auth/logout", serde_json::json!({ "refresh_token": rotated })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = app
+            .clone()
+            .oneshot(post_json("/api/auth/refresh", serde_json::json!({ "refresh_token": rotated })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/users/{}", jane.id))
+                    .header(header::AUTHORIZATION, format!("Bearer {}", access_token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let user = body_json(response).await;
+        assert_eq!(user["data"]["username"], "jane");
+        assert!(user["data"].get("password_hash").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_api_key() {
+        let api_keys = Arc::new(ApiKeyService::new(Arc::new(InMemoryApiKeyRepository::new())));
+        let (_, key) = api_keys
+            .create(&Principal::system(), "reporting", ApiKeyScope::ReadOnly)
+            .await
+            .unwrap();
+        let authenticator = Arc::new(
+            Authenticator::new(Arc::new(JwtVerifier::from_config(&auth_config()).unwrap()))
+                .with_api_keys(api_keys),
+        );
+        let app = create_user_router(service(), Some(authenticator), None);
+
+        let search = Request::builder()
+            .uri("/api/users/filter/age?min_age=1&max_age=150")
+            .header(API_KEY_HEADER, &key)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(search).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut request = list_users();
+        request.headers_mut().insert(API_KEY_HEADER, key.parse().unwrap());
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let create = Request::builder()
+            .method("POST")
+            .uri("/api/users")
+            .header(API_KEY_HEADER, &key)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({
+                    "username": "bot",
+                    "email": "bot@example.com",
+                    "full_name": "Bot",
+                    "password": "Password123",
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let response = app.oneshot(create).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_admins_manage_api_keys() {
+        let api_keys = Arc::new(ApiKeyService::new(Arc::new(InMemoryApiKeyRepository::new())));
+        let (_, admin_key) = api_keys
+            .create(&Principal::system(), "ops", ApiKeyScope::Admin)
+            .await
+            .unwrap();
+        let authenticator = Arc::new(
+            Authenticator::new(Arc::new(JwtVerifier::from_config(&auth_config()).unwrap()))
+                .with_api_keys(api_keys.clone()),
+        );
+        let app = create_api_key_router(api_keys, authenticator, None);
+
+        let create = Request::builder()
+            .method("POST")
+            .uri("/api/api-keys")
+            .header(API_KEY_HEADER, &admin_key)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::json!({ "name": "reporting" }).to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(create).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created = body_json(response).await;
+        assert_eq!(created["data"]["api_key"]["scope"], "read-only");
+        assert!(created["data"]["api_key"].get("key_hash").is_none());
+        let key = created["data"]["key"].as_str().unwrap().to_string();
+
+        let list = |key: &str| {
+            Request::builder()
+                .uri("/api/api-keys")
+                .header(API_KEY_HEADER, key)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = app.clone().oneshot(list(&admin_key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await["data"].as_array().unwrap().len(), 2);
+
+        let response = app.oneshot(list(&key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_does_not_reveal_accounts() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        UserService::new(repository.clone())
+            .create_user(
+                &Principal::system(),
+                CreateUserDto {
+                    username: "jane".to_string(),
+                    email: "jane@example.com".to_string(),
+                    full_name: "Jane Doe".to_string(),
+                    age: None,
+                    password: "Password123".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        let app = create_password_reset_router(Arc::new(PasswordResetService::new(
+            repository,
+            Arc::new(InMemoryPasswordResetRepository::new()),
+            Arc::new(InMemorySessionRepository::new()),
+            chrono::Duration::hours(1),
+        )), None);
+
+        let post_json = |uri: &str, body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        for email in ["jane@example.com", "nobody@example.com"] {
+            let response = app
+                .clone()
+                .oneshot(post_json("/api/auth/password-reset/request", serde_json::json!({ "email": email })))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+        }
+
+        let response = app
+            .oneshot(post_json(
+                "/api/auth/password-reset/confirm",
+                serde_json::json!({ "token": "bogus", "new_password": "NewPassword456" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body_json(response).await["code"], "INVALID_TOKEN");
+    }
 }
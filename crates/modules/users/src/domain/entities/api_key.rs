@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// What an API key may do with the users API.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiKeyScope {
+    /// Read and search users.
+    ReadOnly,
+    /// Everything an admin user may do.
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::ReadOnly => "read-only",
+            ApiKeyScope::Admin => "admin",
+        }
+    }
+}
+
+impl std::str::FromStr for ApiKeyScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read-only" => Ok(ApiKeyScope::ReadOnly),
+            "admin" => Ok(ApiKeyScope::Admin),
+            _ => Err(format!("Unknown API key scope '{}', expected 'read-only' or 'admin'", s)),
+        }
+    }
+}
+
+/// Stored as its [`as_str`](ApiKeyScope::as_str) form in a `VARCHAR` or
+/// `TEXT` column.
+impl Type<Postgres> for ApiKeyScope {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for ApiKeyScope {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for ApiKeyScope {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
+
+/// An API key for service-to-service calls. Only the SHA-256 of the key is
+/// stored; the key itself is shown once, when it is created.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    /// Which service the key was issued to.
+    pub name: String,
+    /// First characters of the key, to recognise it in listings and logs.
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scope: ApiKeyScope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    pub fn new(name: String, prefix: String, key_hash: String, scope: ApiKeyScope) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            prefix,
+            key_hash,
+            scope,
+            created_at: Utc::now(),
+            last_used_at: None,
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+}
@@ -1,3 +1,9 @@
+pub mod api_key;
+pub mod password_reset;
+pub mod session;
 pub mod user;
 
+pub use api_key::*;
+pub use password_reset::*;
+pub use session::*;
 pub use user::*;
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+
+/// A login session. Each refresh rotates `refresh_token_id` to the `jti` of
+/// the newly issued refresh token; presenting an older one revokes the session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// `jti` of the only refresh token currently valid for this session.
+    pub refresh_token_id: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    /// Absolute expiry from `auth.session_timeout_hours`; never extended.
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    pub fn new(user_id: Uuid, refresh_token_id: String, timeout: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            refresh_token_id,
+            created_at: now,
+            last_used_at: now,
+            expires_at: now + timeout,
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && now < self.expires_at
+    }
+}
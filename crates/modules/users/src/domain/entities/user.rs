@@ -1,6 +1,86 @@
 use serde::{Deserialize, Serialize};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use validator::ValidateEmail;
+
+/// Where an account is in its lifecycle.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    /// Registered, email address not confirmed yet.
+    PendingVerification,
+    /// Records created before statuses existed are active.
+    #[default]
+    Active,
+    /// Blocked by an admin: cannot sign in or be updated.
+    Suspended,
+    /// Closed by its owner or an admin.
+    Deactivated,
+}
+
+impl UserStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserStatus::PendingVerification => "pending_verification",
+            UserStatus::Active => "active",
+            UserStatus::Suspended => "suspended",
+            UserStatus::Deactivated => "deactivated",
+        }
+    }
+
+    /// Pending accounts may sign in, so they can confirm their address.
+    pub fn can_sign_in(&self) -> bool {
+        matches!(self, UserStatus::PendingVerification | UserStatus::Active)
+    }
+}
+
+impl std::fmt::Display for UserStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for UserStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending_verification" => Ok(UserStatus::PendingVerification),
+            "active" => Ok(UserStatus::Active),
+            "suspended" => Ok(UserStatus::Suspended),
+            "deactivated" => Ok(UserStatus::Deactivated),
+            _ => Err(format!("Unknown user status '{}'", s)),
+        }
+    }
+}
+
+/// Stored as its [`as_str`](UserStatus::as_str) form in the `VARCHAR(32)`
+/// `status` column (users migration v5).
+impl Type<Postgres> for UserStatus {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for UserStatus {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for UserStatus {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::FromRow)]
 pub struct User {
@@ -9,6 +89,19 @@ pub struct User {
     pub email: String,
     pub full_name: String,
     pub age: Option<i32>,
+    /// Argon2id PHC string; `None` for accounts that cannot sign in with a
+    /// password. Never included in API responses.
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    #[serde(default)]
+    pub status: UserStatus,
+    #[serde(default)]
+    pub email_verified_at: Option<DateTime<Utc>>,
+    /// SHA-256 of the outstanding email verification token.
+    #[serde(default)]
+    pub verification_token_hash: Option<String>,
+    #[serde(default)]
+    pub verification_expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -22,16 +115,26 @@ impl User {
             email,
             full_name,
             age,
+            password_hash: None,
+            status: UserStatus::PendingVerification,
+            email_verified_at: None,
+            verification_token_hash: None,
+            verification_expires_at: None,
             created_at: now,
             updated_at: now,
         }
     }
 
+    pub fn with_password_hash(mut self, password_hash: String) -> Self {
+        self.password_hash = Some(password_hash);
+        self
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.username.is_empty() {
             return Err("Username cannot be empty".to_string());
         }
-        if self.email.is_empty() || !self.email.contains('@') {
+        if !self.email.validate_email() {
             return Err("Invalid email address".to_string());
         }
         if self.full_name.is_empty() {
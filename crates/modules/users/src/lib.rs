@@ -1,6 +1,7 @@
 pub mod constants;
 pub mod domain;
 pub mod delivery;
+pub mod notification;
 pub mod repositories;
 pub mod service;
 pub mod types;
@@ -8,6 +9,7 @@ pub mod types;
 // Re-export commonly used types for convenience
 pub use domain::*;
 pub use delivery::*;
-pub use repositories::{UserRepository, InMemoryUserRepository, USER_MIGRATIONS};
-pub use service::{UserService, IUserService, UserStatistics};
+pub use repositories::{UserRepository, InMemoryUserRepository, SessionRepository, InMemorySessionRepository, ApiKeyRepository, InMemoryApiKeyRepository, PasswordResetRepository, InMemoryPasswordResetRepository, USER_MIGRATIONS};
+pub use service::{UserService, SessionService, ApiKeyService, PasswordResetService, IUserService, UserStatistics};
 pub use constants::*;
+pub use notification::{Notifier, InMemoryNotifier, FileNotifier, SmtpNotifier};
@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::message::Mailbox;
+use uuid::Uuid;
+
+use super::{build_message, parse_mailbox, EmailMessage, NotificationResult, Notifier};
+
+/// Writes each message as an `.eml` file to a spool directory, for
+/// development without a mail server.
+#[derive(Debug, Clone)]
+pub struct FileNotifier {
+    from: Mailbox,
+    dir: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(from: &str, dir: impl Into<PathBuf>) -> NotificationResult<Self> {
+        Ok(Self {
+            from: parse_mailbox(from)?,
+            dir: dir.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    async fn send(&self, message: EmailMessage) -> NotificationResult<()> {
+        let email = build_message(&self.from, &message)?;
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            Utc::now().format("%Y%m%dT%H%M%S"),
+            Uuid::new_v4()
+        ));
+        tokio::fs::write(&path, email.formatted()).await?;
+
+        tracing::info!(to = %message.to, path = %path.display(), "Spooled email");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::NotificationError;
+
+    #[tokio::test]
+    async fn test_messages_are_spooled_as_eml() {
+        let dir = std::env::temp_dir().join(format!("users-mail-{}", Uuid::new_v4()));
+        let notifier = FileNotifier::new("Users API <no-reply@example.com>", &dir).unwrap();
+
+        notifier
+            .send(EmailMessage {
+                to: "jane@example.com".to_string(),
+                subject: "Hello".to_string(),
+                body: "Welcome aboard".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let mut entries = std::fs::read_dir(&dir).unwrap();
+        let path = entries.next().unwrap().unwrap().path();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("To: jane@example.com"));
+        assert!(contents.contains("Subject: Hello"));
+        assert!(contents.contains("Welcome aboard"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_recipient_is_rejected() {
+        let notifier = FileNotifier::new("no-reply@example.com", std::env::temp_dir()).unwrap();
+        let result = notifier
+            .send(EmailMessage {
+                to: "not an address".to_string(),
+                subject: "Hello".to_string(),
+                body: String::new(),
+            })
+            .await;
+        assert!(matches!(result, Err(NotificationError::InvalidAddress(_))));
+    }
+}
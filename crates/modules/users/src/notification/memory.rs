@@ -0,0 +1,34 @@
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+
+use super::{EmailMessage, NotificationResult, Notifier};
+
+/// Records messages instead of sending them; for tests.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryNotifier {
+    sent: Arc<Mutex<Vec<EmailMessage>>>,
+}
+
+impl InMemoryNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every message sent so far, oldest first.
+    pub fn sent(&self) -> Vec<EmailMessage> {
+        self.sent.lock().expect("notifier lock poisoned").clone()
+    }
+
+    /// The latest message sent to `to`.
+    pub fn last_to(&self, to: &str) -> Option<EmailMessage> {
+        self.sent().into_iter().rev().find(|message| message.to == to)
+    }
+}
+
+#[async_trait]
+impl Notifier for InMemoryNotifier {
+    async fn send(&self, message: EmailMessage) -> NotificationResult<()> {
+        self.sent.lock().expect("notifier lock poisoned").push(message);
+        Ok(())
+    }
+}
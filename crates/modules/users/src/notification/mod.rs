@@ -0,0 +1,138 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use lettre::message::{header::ContentType, Mailbox, Message};
+use thiserror::Error;
+use uuid::Uuid;
+
+use core_config::{NotificationConfig, NotificationTransport};
+
+pub mod file;
+pub mod memory;
+pub mod smtp;
+
+pub use file::FileNotifier;
+pub use memory::InMemoryNotifier;
+pub use smtp::SmtpNotifier;
+
+#[derive(Error, Debug)]
+pub enum NotificationError {
+    #[error("Invalid email address '{0}'")]
+    InvalidAddress(String),
+
+    #[error("Cannot build message: {0}")]
+    Message(String),
+
+    #[error("Email transport failed: {0}")]
+    Transport(String),
+
+    #[error("Cannot write message: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type NotificationResult<T> = Result<T, NotificationError>;
+
+/// A plain-text email to one recipient.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Bodies carry verification and reset tokens, so keep them out of logs.
+impl std::fmt::Debug for EmailMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmailMessage")
+            .field("to", &self.to)
+            .field("subject", &self.subject)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Outbound port for messages to users. Implementations deliver one message
+/// at a time and do not retry.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, message: EmailMessage) -> NotificationResult<()>;
+}
+
+/// What a user is notified about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// Sent on registration, to the new address after an email change and
+    /// on `POST /api/users/:id/verify/resend`.
+    VerifyEmail { username: String, user_id: Uuid, token: String, valid_days: i64 },
+    /// Sent to the previous address after an email change.
+    EmailChanged { username: String, new_email: String },
+    /// Sent on `POST /api/auth/password-reset/request` for a known address.
+    PasswordReset { username: String, token: String, valid_minutes: i64 },
+}
+
+impl Notification {
+    pub fn to_message(&self, to: &str) -> EmailMessage {
+        let (subject, body) = match self {
+            Notification::VerifyEmail { username, user_id, token, valid_days } => (
+                "Confirm your email address".to_string(),
+                format!(
+                    "Hello {username},\n\n\
+                     Confirm this address by sending the token below to\n\
+                     POST /api/users/{user_id}/verify within {valid_days} days:\n\n\
+                     {token}\n\n\
+                     If you did not sign up, ignore this message.\n"
+                ),
+            ),
+            Notification::EmailChanged { username, new_email } => (
+                "Your email address was changed".to_string(),
+                format!(
+                    "Hello {username},\n\n\
+                     The email address of your account was changed to {new_email}.\n\
+                     If you did not make this change, contact support right away.\n"
+                ),
+            ),
+            Notification::PasswordReset { username, token, valid_minutes } => (
+                "Reset your password".to_string(),
+                format!(
+                    "Hello {username},\n\n\
+                     Choose a new password by sending the token below with it to\n\
+                     POST /api/auth/password-reset/confirm within {valid_minutes} minutes:\n\n\
+                     {token}\n\n\
+                     The token works once. Resetting signs you out everywhere.\n\
+                     If you did not ask for this, ignore this message.\n"
+                ),
+            ),
+        };
+
+        EmailMessage {
+            to: to.to_string(),
+            subject,
+            body,
+        }
+    }
+}
+
+/// The notifier selected by `notifications.transport`; `None` when disabled.
+pub fn notifier_from_config(config: &NotificationConfig) -> NotificationResult<Option<Arc<dyn Notifier>>> {
+    let notifier: Arc<dyn Notifier> = match config.transport {
+        NotificationTransport::Disabled => return Ok(None),
+        NotificationTransport::File => Arc::new(FileNotifier::new(&config.from, &config.spool_dir)?),
+        NotificationTransport::Smtp => Arc::new(SmtpNotifier::from_config(config)?),
+    };
+    Ok(Some(notifier))
+}
+
+fn parse_mailbox(address: &str) -> NotificationResult<Mailbox> {
+    address
+        .parse()
+        .map_err(|_| NotificationError::InvalidAddress(address.to_string()))
+}
+
+/// RFC 5322 message shared by the SMTP and file transports.
+fn build_message(from: &Mailbox, message: &EmailMessage) -> NotificationResult<Message> {
+    Message::builder()
+        .from(from.clone())
+        .to(parse_mailbox(&message.to)?)
+        .subject(&message.subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(message.body.clone())
+        .map_err(|e| NotificationError::Message(e.to_string()))
+}
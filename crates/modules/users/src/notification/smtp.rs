@@ -0,0 +1,139 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+use core_config::{NotificationConfig, SmtpSecurity};
+use super::{build_message, parse_mailbox, EmailMessage, NotificationError, NotificationResult, Notifier};
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends messages through an SMTP relay.
+#[derive(Clone)]
+pub struct SmtpNotifier {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpNotifier {
+    pub fn from_config(config: &NotificationConfig) -> NotificationResult<Self> {
+        let host = config.smtp_host.as_deref().ok_or_else(|| {
+            NotificationError::Transport("notifications.smtp_host is not set".to_string())
+        })?;
+
+        let builder = match config.smtp_security {
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(|e| NotificationError::Transport(e.to_string()))?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+                .map_err(|e| NotificationError::Transport(e.to_string()))?,
+        };
+        let mut builder = builder.port(config.smtp_port).timeout(Some(SMTP_TIMEOUT));
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            from: parse_mailbox(&config.from)?,
+            transport: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    async fn send(&self, message: EmailMessage) -> NotificationResult<()> {
+        let email = build_message(&self.from, &message)?;
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| NotificationError::Transport(e.to_string()))?;
+
+        tracing::info!(to = %message.to, subject = %message.subject, "Sent email");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_config::NotificationTransport;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Accepts one SMTP session, answers every command with success and
+    /// returns the message data it received.
+    async fn fake_smtp_server() -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut data = String::new();
+            let mut in_data = false;
+
+            writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        writer.write_all(b"250 queued\r\n").await.unwrap();
+                    } else {
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    continue;
+                }
+
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250 localhost\r\n"
+                } else if command.starts_with("DATA") {
+                    in_data = true;
+                    b"354 end with .\r\n"
+                } else if command.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+            data
+        });
+
+        (port, server)
+    }
+
+    #[tokio::test]
+    async fn test_sends_through_smtp_server() {
+        let (port, server) = fake_smtp_server().await;
+        let notifier = SmtpNotifier::from_config(&NotificationConfig {
+            transport: NotificationTransport::Smtp,
+            from: "no-reply@example.com".to_string(),
+            smtp_host: Some("127.0.0.1".to_string()),
+            smtp_port: port,
+            smtp_security: SmtpSecurity::None,
+            ..NotificationConfig::default()
+        })
+        .unwrap();
+
+        notifier
+            .send(EmailMessage {
+                to: "jane@example.com".to_string(),
+                subject: "Confirm your email address".to_string(),
+                body: "token-123".to_string(),
+            })
+            .await
+            .unwrap();
+        drop(notifier);
+
+        let data = server.await.unwrap();
+        assert!(data.contains("To: jane@example.com"));
+        assert!(data.contains("Subject: Confirm your email address"));
+        assert!(data.contains("token-123"));
+    }
+}
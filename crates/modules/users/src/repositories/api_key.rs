@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use pkg::RepositoryResult;
+use baserepository::{BaseRepository, InMemoryBaseRepository};
+use crate::domain::ApiKey;
+
+#[async_trait]
+pub trait ApiKeyRepository: BaseRepository<ApiKey, Uuid> {
+    /// The key whose SHA-256 is `key_hash`, revoked or not.
+    async fn find_by_hash(&self, key_hash: &str) -> RepositoryResult<Option<ApiKey>>;
+
+    /// Record that the key was just used.
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+
+    /// Revoke a key. Returns `false` if it was already revoked or unknown.
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<bool>;
+}
+
+#[derive(Debug, Clone)]
+pub struct InMemoryApiKeyRepository {
+    base: InMemoryBaseRepository<ApiKey, Uuid>,
+}
+
+impl InMemoryApiKeyRepository {
+    pub fn new() -> Self {
+        Self {
+            base: InMemoryBaseRepository::new(),
+        }
+    }
+}
+
+impl Default for InMemoryApiKeyRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BaseRepository<ApiKey, Uuid> for InMemoryApiKeyRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<ApiKey>> {
+        self.base.get(&id).await
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<ApiKey>> {
+        self.base.get_all().await
+    }
+
+    async fn save(&self, entity: ApiKey) -> RepositoryResult<ApiKey> {
+        self.base.insert(entity.id, entity.clone()).await?;
+        Ok(entity)
+    }
+
+    async fn update(&self, id: Uuid, entity: ApiKey) -> RepositoryResult<ApiKey> {
+        self.base.update_entity(id, entity).await
+    }
+
+    async fn delete(&self, id: Uuid) -> RepositoryResult<bool> {
+        self.base.remove(&id).await
+    }
+
+    async fn exists(&self, id: Uuid) -> RepositoryResult<bool> {
+        self.base.contains(&id).await
+    }
+
+    async fn count(&self) -> RepositoryResult<usize> {
+        self.base.count_all().await
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryApiKeyRepository {
+    async fn find_by_hash(&self, key_hash: &str) -> RepositoryResult<Option<ApiKey>> {
+        let keys = self.base.get_all().await?;
+        Ok(keys.into_iter().find(|k| k.key_hash == key_hash))
+    }
+
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()> {
+        if let Some(mut key) = self.base.get(&id).await? {
+            key.last_used_at = Some(Utc::now());
+            self.base.update_entity(id, key).await?;
+        }
+        Ok(())
+    }
+
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<bool> {
+        match self.base.get(&id).await? {
+            Some(mut key) if key.revoked_at.is_none() => {
+                key.revoked_at = Some(Utc::now());
+                self.base.update_entity(id, key).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
@@ -14,7 +14,11 @@ pub trait UserRepository: BaseRepository<User, Uuid> {
 
     async fn find_by_age_range(&self, min_age: i32, max_age: i32) -> RepositoryResult<Vec<User>>;
 
-    async fn create_user(&self, dto: CreateUserDto) -> RepositoryResult<User>;
+    /// Insert a user from `dto`, storing `password_hash` instead of the
+    /// plaintext `dto.password`.
+    async fn create_user(&self, dto: CreateUserDto, password_hash: String) -> RepositoryResult<User>;
 
     async fn update_user(&self, id: Uuid, dto: UpdateUserDto) -> RepositoryResult<User>;
+
+    async fn update_password_hash(&self, id: Uuid, password_hash: String) -> RepositoryResult<()>;
 }
@@ -1,44 +1,9 @@
-
 use core_db::Migration;
 
-const MIGRATION_CREATE_USERS_TABLE: &str = r#"
--- Create users table
-CREATE TABLE IF NOT EXISTS users (
-    id UUID PRIMARY KEY,
-    username VARCHAR(255) NOT NULL UNIQUE,
-    email VARCHAR(255) NOT NULL UNIQUE,
-    full_name VARCHAR(255) NOT NULL,
-    age INTEGER,
-    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
-);
-
--- Create indexes for better query performance
-CREATE INDEX IF NOT EXISTS idx_users_username ON users(username);
-CREATE INDEX IF NOT EXISTS idx_users_email ON users(email);
-CREATE INDEX IF NOT EXISTS idx_users_age ON users(age);
-
--- Create a trigger to auto-update updated_at
-CREATE OR REPLACE FUNCTION update_updated_at_column()
-RETURNS TRIGGER AS $$
-BEGIN
-    NEW.updated_at = CURRENT_TIMESTAMP;
-    RETURN NEW;
-END;
-$$ language 'plpgsql';
-
-CREATE TRIGGER update_users_updated_at BEFORE UPDATE ON users
-    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
-"#;
-
-pub const MIGRATIONS: &[Migration] = &[
-    Migration::new(
-        "users",                         
-        1,                               
-        "create_users_table",            
-        MIGRATION_CREATE_USERS_TABLE,    
-    ),
-];
+/// Migrations of the users module, embedded from `migrations/users`. The
+/// `.up.sql` files are checksummed byte for byte, so they must not be edited
+/// once applied anywhere; add a new version instead.
+pub const MIGRATIONS: &[Migration] = core_db::embed_migrations!("migrations");
 
 #[cfg(test)]
 mod tests {
@@ -47,7 +12,7 @@ mod tests {
     #[test]
     fn test_migrations_array_not_empty() {
         assert!(!MIGRATIONS.is_empty());
-        assert_eq!(MIGRATIONS.len(), 1); 
+        assert_eq!(MIGRATIONS.len(), 6);
     }
 
     #[test]
@@ -63,10 +28,20 @@ mod tests {
     fn test_migrations_are_valid_sql() {
         for migration in MIGRATIONS {
             assert!(!migration.sql.is_empty());
-            assert!(migration.sql.contains("CREATE TABLE"));
+            assert!(migration.sql.contains("CREATE TABLE") || migration.sql.contains("ALTER TABLE"));
         }
     }
 
+    #[test]
+    fn test_checksums_match_databases_migrated_before_the_move_to_files() {
+        let checksums: Vec<String> = MIGRATIONS.iter().map(Migration::legacy_checksum).collect();
+        assert_eq!(
+            checksums,
+            ["924-10-10", "129-10-10", "532-10-10", "481-10-10", "505-10-10", "523-10-10"]
+        );
+        assert!(MIGRATIONS.iter().all(|migration| migration.down_sql.is_some()));
+    }
+
     #[test]
     fn test_migrations_have_unique_versions() {
         let mut versions = std::collections::HashSet::new();
@@ -1,7 +1,25 @@
+pub mod api_key;
 pub mod interface;
 pub mod migration;
+pub mod password_reset;
+#[cfg(feature = "postgres")]
+pub mod postgres_api_key;
+#[cfg(feature = "postgres")]
+pub mod postgres_password_reset;
+#[cfg(feature = "postgres")]
+pub mod postgres_session;
 pub mod repository;
+pub mod session;
 
+pub use api_key::{ApiKeyRepository, InMemoryApiKeyRepository};
 pub use interface::UserRepository;
 pub use migration::MIGRATIONS as USER_MIGRATIONS;
+pub use password_reset::{InMemoryPasswordResetRepository, PasswordResetRepository};
+#[cfg(feature = "postgres")]
+pub use postgres_api_key::PostgresApiKeyRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_password_reset::PostgresPasswordResetRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_session::PostgresSessionRepository;
 pub use repository::InMemoryUserRepository;
+pub use session::{InMemorySessionRepository, SessionRepository};
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use pkg::RepositoryResult;
+use baserepository::{BaseRepository, InMemoryBaseRepository};
+use crate::domain::PasswordResetToken;
+
+#[async_trait]
+pub trait PasswordResetRepository: BaseRepository<PasswordResetToken, Uuid> {
+    async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<PasswordResetToken>>;
+
+    /// Mark the token used if nobody has yet. Returns `false` when it was
+    /// already used, so a token can be redeemed only once.
+    async fn consume(&self, id: Uuid) -> RepositoryResult<bool>;
+
+    /// Mark every unused token of a user as used, returning how many were.
+    async fn invalidate_for_user(&self, user_id: Uuid) -> RepositoryResult<usize>;
+}
+
+#[derive(Debug, Clone)]
+pub struct InMemoryPasswordResetRepository {
+    base: InMemoryBaseRepository<PasswordResetToken, Uuid>,
+    /// Makes read-modify-write operations atomic, like a row lock would.
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl InMemoryPasswordResetRepository {
+    pub fn new() -> Self {
+        Self {
+            base: InMemoryBaseRepository::new(),
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+impl Default for InMemoryPasswordResetRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BaseRepository<PasswordResetToken, Uuid> for InMemoryPasswordResetRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>> {
+        self.base.get(&id).await
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<PasswordResetToken>> {
+        self.base.get_all().await
+    }
+
+    async fn save(&self, entity: PasswordResetToken) -> RepositoryResult<PasswordResetToken> {
+        self.base.insert(entity.id, entity.clone()).await?;
+        Ok(entity)
+    }
+
+    async fn update(&self, id: Uuid, entity: PasswordResetToken) -> RepositoryResult<PasswordResetToken> {
+        self.base.update_entity(id, entity).await
+    }
+
+    async fn delete(&self, id: Uuid) -> RepositoryResult<bool> {
+        self.base.remove(&id).await
+    }
+
+    async fn exists(&self, id: Uuid) -> RepositoryResult<bool> {
+        self.base.contains(&id).await
+    }
+
+    async fn count(&self) -> RepositoryResult<usize> {
+        self.base.count_all().await
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for InMemoryPasswordResetRepository {
+    async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<PasswordResetToken>> {
+        let tokens = self.base.get_all().await?;
+        Ok(tokens.into_iter().find(|t| t.token_hash == token_hash))
+    }
+
+    async fn consume(&self, id: Uuid) -> RepositoryResult<bool> {
+        let _guard = self.write_lock.lock().await;
+
+        match self.base.get(&id).await? {
+            Some(mut token) if token.used_at.is_none() => {
+                token.used_at = Some(Utc::now());
+                self.base.update_entity(id, token).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn invalidate_for_user(&self, user_id: Uuid) -> RepositoryResult<usize> {
+        let _guard = self.write_lock.lock().await;
+
+        let now = Utc::now();
+        let mut invalidated = 0;
+        for mut token in self.base.get_all().await? {
+            if token.user_id == user_id && token.used_at.is_none() {
+                token.used_at = Some(now);
+                self.base.update_entity(token.id, token).await?;
+                invalidated += 1;
+            }
+        }
+        Ok(invalidated)
+    }
+}
@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use pkg::{RepositoryError, RepositoryResult};
+use baserepository::BaseRepository;
+use postgres_adapter::PostgresBaseRepository;
+use crate::domain::PasswordResetToken;
+use super::password_reset::PasswordResetRepository;
+
+/// [`PasswordResetRepository`] over the `password_reset_tokens` table (users migration v6).
+#[derive(Debug, Clone)]
+pub struct PostgresPasswordResetRepository {
+    base: PostgresBaseRepository<PasswordResetToken>,
+}
+
+impl PostgresPasswordResetRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            base: PostgresBaseRepository::new(pool, "password_reset_tokens"),
+        }
+    }
+}
+
+#[async_trait]
+impl BaseRepository<PasswordResetToken, Uuid> for PostgresPasswordResetRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<PasswordResetToken>> {
+        self.base.find_by_id(id).await
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<PasswordResetToken>> {
+        self.base.find_all().await
+    }
+
+    async fn save(&self, entity: PasswordResetToken) -> RepositoryResult<PasswordResetToken> {
+        sqlx::query_as::<_, PasswordResetToken>(
+            "INSERT INTO password_reset_tokens \
+             (id, user_id, token_hash, created_at, expires_at, used_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+        )
+        .bind(entity.id)
+        .bind(entity.user_id)
+        .bind(&entity.token_hash)
+        .bind(entity.created_at)
+        .bind(entity.expires_at)
+        .bind(entity.used_at)
+        .fetch_one(self.base.pool())
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn update(&self, id: Uuid, entity: PasswordResetToken) -> RepositoryResult<PasswordResetToken> {
+        sqlx::query_as::<_, PasswordResetToken>(
+            "UPDATE password_reset_tokens SET expires_at = $2, used_at = $3 \
+             WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .bind(entity.expires_at)
+        .bind(entity.used_at)
+        .fetch_optional(self.base.pool())
+        .await
+        .map_err(RepositoryError::from)?
+        .ok_or(RepositoryError::NotFound(id))
+    }
+
+    async fn delete(&self, id: Uuid) -> RepositoryResult<bool> {
+        sqlx::query("DELETE FROM password_reset_tokens WHERE id = $1")
+            .bind(id)
+            .execute(self.base.pool())
+            .await
+            .map(|result| result.rows_affected() > 0)
+            .map_err(RepositoryError::from)
+    }
+
+    async fn exists(&self, id: Uuid) -> RepositoryResult<bool> {
+        Ok(self.base.find_by_id(id).await?.is_some())
+    }
+
+    async fn count(&self) -> RepositoryResult<usize> {
+        self.base.count().await
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for PostgresPasswordResetRepository {
+    async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<PasswordResetToken>> {
+        self.base.find_one_by_column("token_hash", token_hash).await
+    }
+
+    async fn consume(&self, id: Uuid) -> RepositoryResult<bool> {
+        sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = CURRENT_TIMESTAMP \
+             WHERE id = $1 AND used_at IS NULL",
+        )
+        .bind(id)
+        .execute(self.base.pool())
+        .await
+        .map(|result| result.rows_affected() == 1)
+        .map_err(RepositoryError::from)
+    }
+
+    async fn invalidate_for_user(&self, user_id: Uuid) -> RepositoryResult<usize> {
+        sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = CURRENT_TIMESTAMP \
+             WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(self.base.pool())
+        .await
+        .map(|result| result.rows_affected() as usize)
+        .map_err(RepositoryError::from)
+    }
+}
@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use pkg::{RepositoryError, RepositoryResult};
+use baserepository::BaseRepository;
+use postgres_adapter::PostgresBaseRepository;
+use crate::domain::Session;
+use super::session::SessionRepository;
+
+/// [`SessionRepository`] over the `sessions` table (users migration v3).
+#[derive(Debug, Clone)]
+pub struct PostgresSessionRepository {
+    base: PostgresBaseRepository<Session>,
+}
+
+impl PostgresSessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            base: PostgresBaseRepository::new(pool, "sessions"),
+        }
+    }
+}
+
+#[async_trait]
+impl BaseRepository<Session, Uuid> for PostgresSessionRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>> {
+        self.base.find_by_id(id).await
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<Session>> {
+        self.base.find_all().await
+    }
+
+    async fn save(&self, entity: Session) -> RepositoryResult<Session> {
+        sqlx::query_as::<_, Session>(
+            "INSERT INTO sessions \
+             (id, user_id, refresh_token_id, created_at, last_used_at, expires_at, revoked_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+        )
+        .bind(entity.id)
+        .bind(entity.user_id)
+        .bind(&entity.refresh_token_id)
+        .bind(entity.created_at)
+        .bind(entity.last_used_at)
+        .bind(entity.expires_at)
+        .bind(entity.revoked_at)
+        .fetch_one(self.base.pool())
+        .await
+        .map_err(RepositoryError::from)
+    }
+
+    async fn update(&self, id: Uuid, entity: Session) -> RepositoryResult<Session> {
+        sqlx::query_as::<_, Session>(
+            "UPDATE sessions SET refresh_token_id = $2, last_used_at = $3, \
+             expires_at = $4, revoked_at = $5 WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .bind(&entity.refresh_token_id)
+        .bind(entity.last_used_at)
+        .bind(entity.expires_at)
+        .bind(entity.revoked_at)
+        .fetch_optional(self.base.pool())
+        .await
+        .map_err(RepositoryError::from)?
+        .ok_or(RepositoryError::NotFound(id))
+    }
+
+    async fn delete(&self, id: Uuid) -> RepositoryResult<bool> {
+        sqlx::query("DELETE FROM sessions WHERE id = $1")
+            .bind(id)
+            .execute(self.base.pool())
+            .await
+            .map(|result| result.rows_affected() > 0)
+            .map_err(RepositoryError::from)
+    }
+
+    async fn exists(&self, id: Uuid) -> RepositoryResult<bool> {
+        Ok(self.base.find_by_id(id).await?.is_some())
+    }
+
+    async fn count(&self) -> RepositoryResult<usize> {
+        self.base.count().await
+    }
+}
+
+#[async_trait]
+impl SessionRepository for PostgresSessionRepository {
+    async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>> {
+        self.base.find_all_by_column("user_id", user_id).await
+    }
+
+    async fn rotate(&self, id: Uuid, current: &str, next: &str) -> RepositoryResult<bool> {
+        sqlx::query(
+            "UPDATE sessions SET refresh_token_id = $3, last_used_at = CURRENT_TIMESTAMP \
+             WHERE id = $1 AND refresh_token_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(current)
+        .bind(next)
+        .execute(self.base.pool())
+        .await
+        .map(|result| result.rows_affected() == 1)
+        .map_err(RepositoryError::from)
+    }
+
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<bool> {
+        sqlx::query(
+            "UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP \
+             WHERE id = $1 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(self.base.pool())
+        .await
+        .map(|result| result.rows_affected() == 1)
+        .map_err(RepositoryError::from)
+    }
+
+    async fn revoke_all_for_user(&self, user_id: Uuid) -> RepositoryResult<usize> {
+        sqlx::query(
+            "UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP \
+             WHERE user_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(self.base.pool())
+        .await
+        .map(|result| result.rows_affected() as usize)
+        .map_err(RepositoryError::from)
+    }
+}
@@ -20,16 +20,15 @@ impl InMemoryUserRepository {
     }
 
     pub fn with_data(users: Vec<User>) -> Self {
-        let repo = Self::new();
         Self {
-            base: InMemoryBaseRepository::new(),
+            base: InMemoryBaseRepository::with_entries(users.into_iter().map(|u| (u.id, u))),
         }
     }
 
     async fn check_duplicate_username(&self, username: &str, exclude_id: Option<Uuid>) -> RepositoryResult<()> {
         let users = self.base.get_all().await?;
         for user in users {
-            if user.username == username && exclude_id.map_or(true, |id| user.id != id) {
+            if user.username == username && exclude_id.is_none_or(|id| user.id != id) {
                 return Err(RepositoryError::ValidationError(
                     format!("Username '{}' is already taken", username)
                 ));
@@ -41,7 +40,7 @@ impl InMemoryUserRepository {
     async fn check_duplicate_email(&self, email: &str, exclude_id: Option<Uuid>) -> RepositoryResult<()> {
         let users = self.base.get_all().await?;
         for user in users {
-            if user.email == email && exclude_id.map_or(true, |id| user.id != id) {
+            if user.email == email && exclude_id.is_none_or(|id| user.id != id) {
                 return Err(RepositoryError::ValidationError(
                     format!("Email '{}' is already taken", email)
                 ));
@@ -51,6 +50,12 @@ impl InMemoryUserRepository {
     }
 }
 
+impl Default for InMemoryUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl BaseRepository<User, Uuid> for InMemoryUserRepository {
     async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>> {
@@ -117,8 +122,9 @@ impl UserRepository for InMemoryUserRepository {
             .collect())
     }
 
-    async fn create_user(&self, dto: CreateUserDto) -> RepositoryResult<User> {
-        let user = User::new(dto.username, dto.email, dto.full_name, dto.age);
+    async fn create_user(&self, dto: CreateUserDto, password_hash: String) -> RepositoryResult<User> {
+        let user = User::new(dto.username, dto.email, dto.full_name, dto.age)
+            .with_password_hash(password_hash);
         self.save(user).await
     }
 
@@ -126,7 +132,7 @@ impl UserRepository for InMemoryUserRepository {
         let mut user = self
             .find_by_id(id)
             .await?
-            .ok_or_else(|| RepositoryError::NotFound(id))?;
+            .ok_or(RepositoryError::NotFound(id))?;
 
         if let Some(username) = dto.username {
             user.username = username;
@@ -143,4 +149,15 @@ impl UserRepository for InMemoryUserRepository {
 
         self.update(id, user).await
     }
+
+    async fn update_password_hash(&self, id: Uuid, password_hash: String) -> RepositoryResult<()> {
+        let mut user = self
+            .find_by_id(id)
+            .await?
+            .ok_or(RepositoryError::NotFound(id))?;
+
+        user.password_hash = Some(password_hash);
+        self.base.update_entity(id, user).await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use pkg::{RepositoryError, RepositoryResult};
+use baserepository::{BaseRepository, InMemoryBaseRepository};
+use crate::domain::Session;
+
+#[async_trait]
+pub trait SessionRepository: BaseRepository<Session, Uuid> {
+    async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+
+    /// Replace the session's refresh token ID if it is still `current` and
+    /// the session is not revoked. Returns `false` when another refresh won.
+    async fn rotate(&self, id: Uuid, current: &str, next: &str) -> RepositoryResult<bool>;
+
+    /// Revoke one session. Returns `false` if it was already revoked or unknown.
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<bool>;
+
+    /// Revoke every active session of a user, returning how many were revoked.
+    async fn revoke_all_for_user(&self, user_id: Uuid) -> RepositoryResult<usize>;
+}
+
+#[derive(Debug, Clone)]
+pub struct InMemorySessionRepository {
+    base: InMemoryBaseRepository<Session, Uuid>,
+    /// Makes read-modify-write operations atomic, like a row lock would.
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl InMemorySessionRepository {
+    pub fn new() -> Self {
+        Self {
+            base: InMemoryBaseRepository::new(),
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+impl Default for InMemorySessionRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BaseRepository<Session, Uuid> for InMemorySessionRepository {
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>> {
+        self.base.get(&id).await
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<Session>> {
+        self.base.get_all().await
+    }
+
+    async fn save(&self, entity: Session) -> RepositoryResult<Session> {
+        self.base.insert(entity.id, entity.clone()).await?;
+        Ok(entity)
+    }
+
+    async fn update(&self, id: Uuid, entity: Session) -> RepositoryResult<Session> {
+        self.base.update_entity(id, entity).await
+    }
+
+    async fn delete(&self, id: Uuid) -> RepositoryResult<bool> {
+        self.base.remove(&id).await
+    }
+
+    async fn exists(&self, id: Uuid) -> RepositoryResult<bool> {
+        self.base.contains(&id).await
+    }
+
+    async fn count(&self) -> RepositoryResult<usize> {
+        self.base.count_all().await
+    }
+}
+
+#[async_trait]
+impl SessionRepository for InMemorySessionRepository {
+    async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>> {
+        let sessions = self.base.get_all().await?;
+        Ok(sessions.into_iter().filter(|s| s.user_id == user_id).collect())
+    }
+
+    async fn rotate(&self, id: Uuid, current: &str, next: &str) -> RepositoryResult<bool> {
+        let _guard = self.write_lock.lock().await;
+
+        let mut session = self
+            .base
+            .get(&id)
+            .await?
+            .ok_or(RepositoryError::NotFound(id))?;
+        if session.revoked_at.is_some() || session.refresh_token_id != current {
+            return Ok(false);
+        }
+
+        session.refresh_token_id = next.to_string();
+        session.last_used_at = Utc::now();
+        self.base.update_entity(id, session).await?;
+        Ok(true)
+    }
+
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<bool> {
+        let _guard = self.write_lock.lock().await;
+
+        match self.base.get(&id).await? {
+            Some(mut session) if session.revoked_at.is_none() => {
+                session.revoked_at = Some(Utc::now());
+                self.base.update_entity(id, session).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn revoke_all_for_user(&self, user_id: Uuid) -> RepositoryResult<usize> {
+        let _guard = self.write_lock.lock().await;
+
+        let now = Utc::now();
+        let mut revoked = 0;
+        for mut session in self.base.get_all().await? {
+            if session.user_id == user_id && session.revoked_at.is_none() {
+                session.revoked_at = Some(now);
+                self.base.update_entity(session.id, session).await?;
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+}
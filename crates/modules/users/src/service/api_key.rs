@@ -0,0 +1,201 @@
+use std::sync::Arc;
+use axum::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use pkg::RepositoryError;
+use core_auth::{ApiKeyVerifier, AuthError, AuthResult, Principal, ADMIN_ROLE, SERVICE_ROLE};
+use crate::constants::{UserError, UserResult};
+use crate::domain::{ApiKey, ApiKeyScope};
+use crate::repositories::ApiKeyRepository;
+use super::policy::{UserAction, UserPolicy};
+use super::token::{generate_token, hash_token};
+
+/// Marks API keys of this service, so leaked keys are easy to spot.
+const KEY_PREFIX: &str = "uk_";
+
+/// Characters of a key kept in clear as [`ApiKey::prefix`].
+const DISPLAY_PREFIX_LEN: usize = KEY_PREFIX.len() + 8;
+
+/// How stale [`ApiKey::last_used_at`] may get, so a busy key is not written
+/// on every request.
+const LAST_USED_INTERVAL_SECS: i64 = 60;
+
+/// Issues, lists and revokes API keys, and verifies them for
+/// [`core_auth::require_auth`]. Only the SHA-256 of a key is stored.
+pub struct ApiKeyService<R: ApiKeyRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: ApiKeyRepository> ApiKeyService<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    /// Create a key for service `name`. The returned key is not stored and
+    /// cannot be shown again.
+    pub async fn create(
+        &self,
+        caller: &Principal,
+        name: &str,
+        scope: ApiKeyScope,
+    ) -> UserResult<(ApiKey, String)> {
+        UserPolicy::authorize(caller, UserAction::ManageApiKeys)?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(UserError::ValidationError("API key name cannot be empty".to_string()));
+        }
+
+        let key = generate_token(KEY_PREFIX);
+        let api_key = ApiKey::new(
+            name.to_string(),
+            key[..DISPLAY_PREFIX_LEN].to_string(),
+            hash_token(&key),
+            scope,
+        );
+        let api_key = self.repository.save(api_key).await?;
+
+        tracing::info!(api_key_id = %api_key.id, name = %api_key.name, scope = scope.as_str(), "Created API key");
+        Ok((api_key, key))
+    }
+
+    /// Every key, revoked ones included, oldest first.
+    pub async fn list(&self, caller: &Principal) -> UserResult<Vec<ApiKey>> {
+        UserPolicy::authorize(caller, UserAction::ManageApiKeys)?;
+
+        let mut keys = self.repository.find_all().await?;
+        keys.sort_by_key(|k| k.created_at);
+        Ok(keys)
+    }
+
+    /// Revoke a key. Returns `false` if it was already revoked.
+    pub async fn revoke(&self, caller: &Principal, id: Uuid) -> UserResult<bool> {
+        UserPolicy::authorize(caller, UserAction::ManageApiKeys)?;
+
+        if !self.repository.exists(id).await? {
+            return Err(RepositoryError::NotFound(id).into());
+        }
+        let revoked = self.repository.revoke(id).await?;
+        if revoked {
+            tracing::info!(api_key_id = %id, by = %caller.subject, "Revoked API key");
+        }
+        Ok(revoked)
+    }
+}
+
+#[async_trait]
+impl<R: ApiKeyRepository + Send + Sync> ApiKeyVerifier for ApiKeyService<R> {
+    async fn verify_api_key(&self, key: &str) -> AuthResult<Principal> {
+        let api_key = self
+            .repository
+            .find_by_hash(&hash_token(key))
+            .await
+            .map_err(|e| AuthError::Configuration(format!("cannot look up API key: {}", e)))?
+            .filter(ApiKey::is_active)
+            .ok_or_else(|| AuthError::InvalidToken("unknown or revoked API key".to_string()))?;
+
+        let recently_used = api_key.last_used_at.is_some_and(|used| {
+            Utc::now() - used < chrono::Duration::seconds(LAST_USED_INTERVAL_SECS)
+        });
+        if !recently_used {
+            if let Err(e) = self.repository.touch(api_key.id).await {
+                tracing::warn!(api_key_id = %api_key.id, "Failed to record API key use: {}", e);
+            }
+        }
+
+        let mut roles = vec![SERVICE_ROLE.to_string()];
+        if api_key.scope == ApiKeyScope::Admin {
+            roles.push(ADMIN_ROLE.to_string());
+        }
+        Ok(Principal {
+            subject: format!("apikey:{}", api_key.name),
+            user_id: None,
+            roles,
+            scopes: vec![api_key.scope.as_str().to_string()],
+            expires_at: u64::MAX,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::InMemoryApiKeyRepository;
+
+    fn service() -> ApiKeyService<InMemoryApiKeyRepository> {
+        ApiKeyService::new(Arc::new(InMemoryApiKeyRepository::new()))
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_stored_hashed_and_verify() {
+        let service = service();
+        let (api_key, key) = service
+            .create(&Principal::system(), "reporting", ApiKeyScope::ReadOnly)
+            .await
+            .unwrap();
+
+        assert!(key.starts_with(&api_key.prefix));
+        assert_ne!(api_key.key_hash, key);
+        assert_eq!(api_key.key_hash.len(), 64);
+
+        let principal = service.verify_api_key(&key).await.unwrap();
+        assert_eq!(principal.subject, "apikey:reporting");
+        assert!(principal.has_role(SERVICE_ROLE));
+        assert!(!principal.is_admin());
+
+        assert!(service.verify_api_key("uk_not-a-key").await.is_err());
+        let listed = service.list(&Principal::system()).await.unwrap();
+        assert!(listed[0].last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_last_use_is_not_written_on_every_request() {
+        let service = service();
+        let (_, key) = service
+            .create(&Principal::system(), "reporting", ApiKeyScope::ReadOnly)
+            .await
+            .unwrap();
+
+        service.verify_api_key(&key).await.unwrap();
+        let first_use = service.list(&Principal::system()).await.unwrap()[0].last_used_at;
+        service.verify_api_key(&key).await.unwrap();
+        let second_use = service.list(&Principal::system()).await.unwrap()[0].last_used_at;
+
+        assert!(first_use.is_some());
+        assert_eq!(first_use, second_use);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_keys_are_rejected() {
+        let service = service();
+        let (api_key, key) = service
+            .create(&Principal::system(), "billing", ApiKeyScope::Admin)
+            .await
+            .unwrap();
+        assert!(service.verify_api_key(&key).await.unwrap().is_admin());
+
+        assert!(service.revoke(&Principal::system(), api_key.id).await.unwrap());
+        assert!(!service.revoke(&Principal::system(), api_key.id).await.unwrap());
+        assert!(matches!(
+            service.verify_api_key(&key).await,
+            Err(AuthError::InvalidToken(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_only_admins_manage_keys() {
+        let service = service();
+        let user_id = Uuid::new_v4();
+        let user = Principal {
+            subject: user_id.to_string(),
+            user_id: Some(user_id),
+            roles: vec![core_auth::USER_ROLE.to_string()],
+            scopes: Vec::new(),
+            expires_at: u64::MAX,
+        };
+
+        assert!(service.create(&user, "mine", ApiKeyScope::Admin).await.is_err());
+        assert!(service.list(&user).await.is_err());
+    }
+}
@@ -1,30 +1,47 @@
+use core_auth::Principal;
 use uuid::Uuid;
 
-use pkg::RepositoryResult;
+use crate::constants::UserResult;
 use crate::domain::User;
-use crate::delivery::http::dto::{CreateUserDto, UpdateUserDto};
+use crate::delivery::http::dto::{ChangePasswordDto, CreateUserDto, UpdateUserDto};
 
 #[async_trait::async_trait]
 pub trait IUserService {
-    async fn create_user(&self, dto: CreateUserDto) -> RepositoryResult<User>;
+    async fn create_user(&self, caller: &Principal, dto: CreateUserDto) -> UserResult<User>;
     
-    async fn get_user(&self, id: Uuid) -> RepositoryResult<User>;
+    async fn get_user(&self, caller: &Principal, id: Uuid) -> UserResult<User>;
     
-    async fn get_all_users(&self) -> RepositoryResult<Vec<User>>;
+    async fn get_all_users(&self, caller: &Principal) -> UserResult<Vec<User>>;
     
-    async fn update_user(&self, id: Uuid, dto: UpdateUserDto) -> RepositoryResult<User>;
+    async fn update_user(&self, caller: &Principal, id: Uuid, dto: UpdateUserDto) -> UserResult<User>;
     
-    async fn delete_user(&self, id: Uuid) -> RepositoryResult<bool>;
+    async fn authenticate(&self, username: &str, password: &str) -> UserResult<User>;
     
-    async fn find_by_username(&self, username: &str) -> RepositoryResult<Option<User>>;
+    async fn change_password(&self, caller: &Principal, id: Uuid, dto: ChangePasswordDto) -> UserResult<()>;
     
-    async fn find_by_email(&self, email: &str) -> RepositoryResult<Option<User>>;
+    async fn issue_verification_token(&self, caller: &Principal, id: Uuid) -> UserResult<String>;
     
-    async fn get_users_by_age_range(&self, min_age: i32, max_age: i32) -> RepositoryResult<Vec<User>>;
+    async fn resend_verification(&self, caller: &Principal, id: Uuid) -> UserResult<()>;
     
-    async fn get_user_count(&self) -> RepositoryResult<usize>;
+    async fn verify_email(&self, caller: &Principal, id: Uuid, token: &str) -> UserResult<User>;
     
-    async fn get_statistics(&self) -> RepositoryResult<UserStatistics>;
+    async fn suspend_user(&self, caller: &Principal, id: Uuid) -> UserResult<User>;
+    
+    async fn reactivate_user(&self, caller: &Principal, id: Uuid) -> UserResult<User>;
+    
+    async fn deactivate_user(&self, caller: &Principal, id: Uuid) -> UserResult<User>;
+    
+    async fn delete_user(&self, caller: &Principal, id: Uuid) -> UserResult<bool>;
+    
+    async fn find_by_username(&self, caller: &Principal, username: &str) -> UserResult<Option<User>>;
+    
+    async fn find_by_email(&self, caller: &Principal, email: &str) -> UserResult<Option<User>>;
+    
+    async fn get_users_by_age_range(&self, caller: &Principal, min_age: i32, max_age: i32) -> UserResult<Vec<User>>;
+    
+    async fn get_user_count(&self, caller: &Principal) -> UserResult<usize>;
+    
+    async fn get_statistics(&self, caller: &Principal) -> UserResult<UserStatistics>;
 }
 
 #[derive(Debug, Clone)]
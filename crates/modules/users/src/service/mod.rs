@@ -1,5 +1,16 @@
+pub mod api_key;
 pub mod interface;
+pub mod password;
+pub mod password_reset;
+pub mod policy;
+#[allow(clippy::module_inception)]
 pub mod service;
+pub mod session;
+mod token;
 
+pub use api_key::ApiKeyService;
 pub use interface::{IUserService, UserStatistics};
+pub use password_reset::PasswordResetService;
+pub use policy::{UserAction, UserPolicy};
 pub use service::UserService;
+pub use session::SessionService;
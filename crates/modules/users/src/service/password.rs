@@ -0,0 +1,88 @@
+use std::sync::OnceLock;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use pkg::utils::validation::is_strong_password;
+use pkg::RepositoryError;
+
+use crate::constants::{UserError, UserResult};
+
+/// Hash `password` with Argon2id (default parameters) into a PHC string.
+/// Hashing is CPU-bound, so it runs on the blocking thread pool.
+pub async fn hash_password(password: &str) -> UserResult<String> {
+    if !is_strong_password(password) {
+        return Err(UserError::WeakPassword);
+    }
+
+    let password = password.to_string();
+    run_blocking(move || hash_blocking(&password)).await?
+}
+
+/// Check `password` against a stored PHC string. A missing hash still costs
+/// one verification so unknown accounts can't be told apart by timing.
+pub async fn verify_password(password: &str, password_hash: Option<&str>) -> UserResult<bool> {
+    let password = password.to_string();
+    let stored = password_hash.map(str::to_string);
+
+    run_blocking(move || match stored {
+        Some(stored) => verify_blocking(&password, &stored),
+        None => {
+            let _ = verify_blocking(&password, dummy_hash());
+            false
+        }
+    })
+    .await
+}
+
+fn hash_blocking(password: &str) -> UserResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| RepositoryError::InternalError(format!("password hashing failed: {}", e)).into())
+}
+
+fn verify_blocking(password: &str, stored: &str) -> bool {
+    match PasswordHash::new(stored) {
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+        Err(e) => {
+            tracing::warn!("Stored password hash is malformed: {}", e);
+            false
+        }
+    }
+}
+
+fn dummy_hash() -> &'static str {
+    static DUMMY: OnceLock<String> = OnceLock::new();
+    DUMMY.get_or_init(|| hash_blocking("Dummy-password-0").unwrap_or_default())
+}
+
+async fn run_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> UserResult<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| RepositoryError::InternalError(format!("password task failed: {}", e)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hash_and_verify() {
+        let hash = hash_password("Correct-Horse1").await.unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+
+        assert!(verify_password("Correct-Horse1", Some(&hash)).await.unwrap());
+        assert!(!verify_password("correct-horse1", Some(&hash)).await.unwrap());
+        assert!(!verify_password("Correct-Horse1", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_weak_passwords_are_rejected() {
+        assert!(matches!(hash_password("password").await, Err(UserError::WeakPassword)));
+    }
+}
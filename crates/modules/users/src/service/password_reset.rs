@@ -0,0 +1,206 @@
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+
+use crate::constants::{UserError, UserResult};
+use crate::domain::PasswordResetToken;
+use crate::notification::{Notification, Notifier};
+use crate::repositories::{PasswordResetRepository, SessionRepository, UserRepository};
+use super::password::hash_password;
+use super::token::{generate_token, hash_token};
+
+/// Self-service password resets for users who cannot sign in.
+///
+/// A reset token is mailed to the account's address and only its SHA-256 is
+/// stored. Requesting a new token supersedes older ones; redeeming one sets
+/// the password and revokes every session of the user.
+pub struct PasswordResetService<R: UserRepository, P: PasswordResetRepository, S: SessionRepository> {
+    users: Arc<R>,
+    resets: Arc<P>,
+    sessions: Arc<S>,
+    notifier: Option<Arc<dyn Notifier>>,
+    ttl: Duration,
+}
+
+impl<R, P, S> PasswordResetService<R, P, S>
+where
+    R: UserRepository,
+    P: PasswordResetRepository,
+    S: SessionRepository,
+{
+    pub fn new(users: Arc<R>, resets: Arc<P>, sessions: Arc<S>, ttl: Duration) -> Self {
+        Self {
+            users,
+            resets,
+            sessions,
+            notifier: None,
+            ttl,
+        }
+    }
+
+    /// Mail reset tokens. Without a notifier tokens are still issued but
+    /// nobody receives them.
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Issue a reset token for the account registered with `email` and mail
+    /// it. Unknown addresses and accounts that cannot sign in get no token,
+    /// and `None` is returned, so callers must not reveal the outcome.
+    pub async fn request(&self, email: &str) -> UserResult<Option<String>> {
+        let user = match self.users.find_by_email(email).await? {
+            Some(user) if user.status.can_sign_in() => user,
+            _ => return Ok(None),
+        };
+
+        self.resets.invalidate_for_user(user.id).await?;
+        let token = generate_token("");
+        self.resets
+            .save(PasswordResetToken::new(user.id, hash_token(&token), self.ttl))
+            .await?;
+
+        tracing::info!(user_id = %user.id, "Password reset requested");
+        if let Some(notifier) = &self.notifier {
+            let notification = Notification::PasswordReset {
+                username: user.username.clone(),
+                token: token.clone(),
+                valid_minutes: self.ttl.num_minutes(),
+            };
+            if let Err(e) = notifier.send(notification.to_message(&user.email)).await {
+                tracing::warn!(user_id = %user.id, "Failed to send password reset email: {}", e);
+            }
+        }
+        Ok(Some(token))
+    }
+
+    /// Set a new password with a reset token. The token is spent even if the
+    /// account turns out to be inactive; weak passwords are rejected first so
+    /// they do not spend it.
+    pub async fn confirm(&self, token: &str, new_password: &str) -> UserResult<()> {
+        let password_hash = hash_password(new_password).await?;
+
+        let reset = self
+            .resets
+            .find_by_hash(&hash_token(token))
+            .await?
+            .filter(|reset| reset.is_usable(Utc::now()))
+            .ok_or(UserError::InvalidResetToken)?;
+        if !self.resets.consume(reset.id).await? {
+            return Err(UserError::InvalidResetToken);
+        }
+
+        let user = self
+            .users
+            .find_by_id(reset.user_id)
+            .await?
+            .ok_or(UserError::InvalidResetToken)?;
+        if !user.status.can_sign_in() {
+            return Err(UserError::AccountInactive(user.status));
+        }
+
+        self.users.update_password_hash(user.id, password_hash).await?;
+        self.resets.invalidate_for_user(user.id).await?;
+        let revoked = self.sessions.revoke_all_for_user(user.id).await?;
+
+        tracing::info!(user_id = %user.id, revoked, "Password reset, sessions revoked");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use baserepository::BaseRepository;
+    use core_auth::Principal;
+    use crate::delivery::http::dto::CreateUserDto;
+    use crate::domain::{Session, User};
+    use crate::notification::InMemoryNotifier;
+    use crate::repositories::{InMemoryPasswordResetRepository, InMemorySessionRepository, InMemoryUserRepository};
+    use crate::service::UserService;
+
+    type Service = PasswordResetService<
+        InMemoryUserRepository,
+        InMemoryPasswordResetRepository,
+        InMemorySessionRepository,
+    >;
+
+    struct Fixture {
+        users: UserService<InMemoryUserRepository>,
+        resets: Arc<InMemoryPasswordResetRepository>,
+        sessions: Arc<InMemorySessionRepository>,
+        service: Service,
+        jane: User,
+    }
+
+    async fn fixture(ttl: Duration) -> Fixture {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        let resets = Arc::new(InMemoryPasswordResetRepository::new());
+        let sessions = Arc::new(InMemorySessionRepository::new());
+        let users = UserService::new(repository.clone());
+        let jane = users
+            .create_user(
+                &Principal::system(),
+                CreateUserDto {
+                    username: "jane".to_string(),
+                    email: "jane@example.com".to_string(),
+                    full_name: "Jane Doe".to_string(),
+                    age: None,
+                    password: "Password123".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        let service = PasswordResetService::new(repository, resets.clone(), sessions.clone(), ttl);
+
+        Fixture { users, resets, sessions, service, jane }
+    }
+
+    fn invalid_token(result: UserResult<()>) -> bool {
+        matches!(result, Err(UserError::InvalidResetToken))
+    }
+
+    #[tokio::test]
+    async fn test_reset_is_single_use_and_ends_sessions() {
+        let f = fixture(Duration::minutes(30)).await;
+        f.sessions.save(Session::new(f.jane.id, "jti".to_string(), Duration::hours(1))).await.unwrap();
+
+        assert!(f.service.request("nobody@example.com").await.unwrap().is_none());
+        let superseded = f.service.request("jane@example.com").await.unwrap().unwrap();
+        let token = f.service.request("jane@example.com").await.unwrap().unwrap();
+        assert!(invalid_token(f.service.confirm(&superseded, "NewPassword456").await));
+
+        assert!(matches!(
+            f.service.confirm(&token, "weak").await,
+            Err(UserError::WeakPassword)
+        ));
+        f.service.confirm(&token, "NewPassword456").await.unwrap();
+        assert!(invalid_token(f.service.confirm(&token, "OtherPassword789").await));
+
+        assert!(f.users.authenticate("jane", "NewPassword456").await.is_ok());
+        assert!(f.users.authenticate("jane", "Password123").await.is_err());
+        assert!(f.sessions.find_all().await.unwrap().iter().all(|s| s.revoked_at.is_some()));
+        assert!(f.resets.find_all().await.unwrap().iter().all(|t| t.used_at.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_expired_tokens_are_rejected() {
+        let f = fixture(Duration::minutes(-1)).await;
+
+        let token = f.service.request("jane@example.com").await.unwrap().unwrap();
+        assert!(invalid_token(f.service.confirm(&token, "NewPassword456").await));
+    }
+
+    #[tokio::test]
+    async fn test_reset_email() {
+        let notifier = Arc::new(InMemoryNotifier::new());
+        let f = fixture(Duration::minutes(30)).await;
+        let service = f.service.with_notifier(notifier.clone());
+
+        let token = service.request("jane@example.com").await.unwrap().unwrap();
+        let message = notifier.last_to("jane@example.com").unwrap();
+        assert_eq!(message.subject, "Reset your password");
+        assert!(message.body.contains(&token));
+        assert!(message.body.contains("30 minutes"));
+        assert!(!f.resets.find_all().await.unwrap()[0].token_hash.contains(&token));
+    }
+}
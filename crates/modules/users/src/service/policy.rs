@@ -0,0 +1,162 @@
+use core_auth::{Principal, SERVICE_ROLE};
+use pkg::{RepositoryError, RepositoryResult};
+use uuid::Uuid;
+
+/// An operation on users, checked by [`UserPolicy::authorize`] before the
+/// service touches the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserAction {
+    Create,
+    Read(Uuid),
+    List,
+    Search,
+    Update(Uuid),
+    ChangePassword(Uuid),
+    VerifyEmail(Uuid),
+    Suspend(Uuid),
+    Reactivate(Uuid),
+    Deactivate(Uuid),
+    Delete(Uuid),
+    RevokeSessions(Uuid),
+    ViewStatistics,
+    ManageApiKeys,
+}
+
+/// Role rules for the users module:
+///
+/// - admins may do anything;
+/// - read-only API keys may only read and search users;
+/// - listing every user, deleting users, revoking their sessions,
+///   statistics and API keys are admin-only;
+/// - suspending and reactivating accounts is admin-only;
+/// - a user may update, verify and deactivate only their own account;
+/// - any authenticated caller may create, read and search users.
+pub struct UserPolicy;
+
+impl UserPolicy {
+    pub fn authorize(principal: &Principal, action: UserAction) -> RepositoryResult<()> {
+        if principal.is_admin() {
+            return Ok(());
+        }
+
+        if principal.has_role(SERVICE_ROLE) {
+            return match action {
+                UserAction::Read(_) | UserAction::Search => Ok(()),
+                _ => Err(RepositoryError::Forbidden(format!(
+                    "{} is not allowed with a read-only API key",
+                    action.describe()
+                ))),
+            };
+        }
+
+        match action {
+            UserAction::Create | UserAction::Read(_) | UserAction::Search => Ok(()),
+            UserAction::Update(id) if principal.user_id == Some(id) => Ok(()),
+            UserAction::Update(_) => Err(RepositoryError::Forbidden(
+                "Users may only update their own record".to_string(),
+            )),
+            UserAction::ChangePassword(id) if principal.user_id == Some(id) => Ok(()),
+            UserAction::ChangePassword(_) => Err(RepositoryError::Forbidden(
+                "Users may only change their own password".to_string(),
+            )),
+            UserAction::VerifyEmail(id) | UserAction::Deactivate(id)
+                if principal.user_id == Some(id) =>
+            {
+                Ok(())
+            }
+            UserAction::VerifyEmail(_) | UserAction::Deactivate(_) => Err(RepositoryError::Forbidden(
+                format!("{} is only allowed on one's own account", action.describe()),
+            )),
+            UserAction::List
+            | UserAction::Suspend(_)
+            | UserAction::Reactivate(_)
+            | UserAction::Delete(_)
+            | UserAction::RevokeSessions(_)
+            | UserAction::ViewStatistics
+            | UserAction::ManageApiKeys => {
+                Err(RepositoryError::Forbidden(format!(
+                    "{} requires the admin role",
+                    action.describe()
+                )))
+            }
+        }
+    }
+}
+
+impl UserAction {
+    fn describe(&self) -> &'static str {
+        match self {
+            UserAction::Create => "Creating users",
+            UserAction::Read(_) => "Reading users",
+            UserAction::List => "Listing all users",
+            UserAction::Search => "Searching users",
+            UserAction::Update(_) => "Updating users",
+            UserAction::ChangePassword(_) => "Changing passwords",
+            UserAction::VerifyEmail(_) => "Verifying email addresses",
+            UserAction::Suspend(_) => "Suspending users",
+            UserAction::Reactivate(_) => "Reactivating users",
+            UserAction::Deactivate(_) => "Deactivating accounts",
+            UserAction::Delete(_) => "Deleting users",
+            UserAction::RevokeSessions(_) => "Revoking sessions",
+            UserAction::ViewStatistics => "Viewing user statistics",
+            UserAction::ManageApiKeys => "Managing API keys",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: Uuid) -> Principal {
+        Principal {
+            subject: id.to_string(),
+            user_id: Some(id),
+            roles: vec!["user".to_string()],
+            scopes: Vec::new(),
+            expires_at: u64::MAX,
+        }
+    }
+
+    #[test]
+    fn test_admin_only_actions() {
+        let me = Uuid::new_v4();
+        for action in [UserAction::List, UserAction::Delete(me), UserAction::ViewStatistics] {
+            assert!(matches!(
+                UserPolicy::authorize(&user(me), action),
+                Err(RepositoryError::Forbidden(_))
+            ));
+            assert!(UserPolicy::authorize(&Principal::system(), action).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_users_update_only_themselves() {
+        let me = Uuid::new_v4();
+        assert!(UserPolicy::authorize(&user(me), UserAction::Update(me)).is_ok());
+        assert!(UserPolicy::authorize(&user(me), UserAction::Update(Uuid::new_v4())).is_err());
+        assert!(UserPolicy::authorize(&user(me), UserAction::Read(Uuid::new_v4())).is_ok());
+    }
+
+    #[test]
+    fn test_read_only_api_keys() {
+        let service = Principal {
+            subject: "apikey:reporting".to_string(),
+            user_id: None,
+            roles: vec![SERVICE_ROLE.to_string()],
+            scopes: vec!["read-only".to_string()],
+            expires_at: u64::MAX,
+        };
+        assert!(UserPolicy::authorize(&service, UserAction::Read(Uuid::new_v4())).is_ok());
+        assert!(UserPolicy::authorize(&service, UserAction::Search).is_ok());
+        for action in [
+            UserAction::Create,
+            UserAction::List,
+            UserAction::ViewStatistics,
+            UserAction::Update(Uuid::new_v4()),
+            UserAction::ManageApiKeys,
+        ] {
+            assert!(UserPolicy::authorize(&service, action).is_err());
+        }
+    }
+}
@@ -1,125 +1,462 @@
 use std::sync::Arc;
 use uuid::Uuid;
 use async_trait::async_trait;
+use chrono::Utc;
+use core_auth::Principal;
+use pkg::utils::datetime_utils::{add_days, is_past};
 
-use pkg::{RepositoryError, RepositoryResult};
-use crate::domain::User;
-use crate::delivery::http::dto::{CreateUserDto, UpdateUserDto};
-use crate::repositories::UserRepository;
+use crate::constants::{UserError, UserResult};
+use crate::domain::{User, UserStatus};
+use crate::delivery::http::dto::{ChangePasswordDto, CreateUserDto, UpdateUserDto};
+use crate::notification::{Notification, Notifier};
+use crate::repositories::{SessionRepository, UserRepository};
 use super::interface::{IUserService, UserStatistics};
+use super::password::{hash_password, verify_password};
+use super::policy::{UserAction, UserPolicy};
+use super::token::{generate_token, hash_token};
+
+/// How long an email verification token stays valid.
+const VERIFICATION_TOKEN_TTL_DAYS: i64 = 3;
 
 pub struct UserService<R: UserRepository> {
     repository: Arc<R>,
+    notifier: Option<Arc<dyn Notifier>>,
+    sessions: Option<Arc<dyn SessionRepository + Send + Sync>>,
 }
 
 impl<R: UserRepository> UserService<R> {
     pub fn new(repository: Arc<R>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            notifier: None,
+            sessions: None,
+        }
+    }
+
+    /// Email users on registration and email changes. Without a notifier
+    /// addresses cannot be verified, so accounts start out active.
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Revoke the sessions of users who are suspended or deactivated, so
+    /// their refresh tokens stop working right away.
+    pub fn with_sessions(mut self, sessions: Arc<dyn SessionRepository + Send + Sync>) -> Self {
+        self.sessions = Some(sessions);
+        self
     }
 
-    pub async fn create_user(&self, dto: CreateUserDto) -> RepositoryResult<User> {
-        if let Some(_existing) = self.repository.find_by_username(&dto.username).await? {
-            return Err(RepositoryError::ValidationError(format!(
-                "Username '{}' is already taken",
-                dto.username
-            )));
+    pub async fn create_user(&self, caller: &Principal, dto: CreateUserDto) -> UserResult<User> {
+        UserPolicy::authorize(caller, UserAction::Create)?;
+        Self::check_age(dto.age)?;
+
+        if self.repository.find_by_username(&dto.username).await?.is_some() {
+            return Err(UserError::UsernameTaken(dto.username));
         }
 
-        if let Some(_existing) = self.repository.find_by_email(&dto.email).await? {
-            return Err(RepositoryError::ValidationError(format!(
-                "Email '{}' is already registered",
-                dto.email
-            )));
+        if self.repository.find_by_email(&dto.email).await?.is_some() {
+            return Err(UserError::EmailTaken(dto.email));
         }
 
+        let password_hash = hash_password(&dto.password).await?;
+        let (username, email) = (dto.username.clone(), dto.email.clone());
+        let user = self
+            .repository
+            .create_user(dto, password_hash)
+            .await
+            .map_err(|e| UserError::from_write(e, &username, &email))?;
 
-        self.repository.create_user(dto).await
+        self.send_verification(user).await
     }
 
-    pub async fn get_user(&self, id: Uuid) -> RepositoryResult<User> {
+    pub async fn get_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        UserPolicy::authorize(caller, UserAction::Read(id))?;
         self.repository
             .find_by_id(id)
             .await?
-            .ok_or(RepositoryError::NotFound(id))
+            .ok_or(UserError::UserNotFound(id))
     }
 
-    pub async fn get_all_users(&self) -> RepositoryResult<Vec<User>> {
-        self.repository.find_all().await
+    pub async fn get_all_users(&self, caller: &Principal) -> UserResult<Vec<User>> {
+        UserPolicy::authorize(caller, UserAction::List)?;
+        Ok(self.repository.find_all().await?)
     }
 
-    pub async fn update_user(&self, id: Uuid, dto: UpdateUserDto) -> RepositoryResult<User> {
+    pub async fn update_user(
+        &self,
+        caller: &Principal,
+        id: Uuid,
+        dto: UpdateUserDto,
+    ) -> UserResult<User> {
+        UserPolicy::authorize(caller, UserAction::Update(id))?;
+        Self::check_age(dto.age)?;
 
         let existing = self
             .repository
             .find_by_id(id)
             .await?
-            .ok_or(RepositoryError::NotFound(id))?;
+            .ok_or(UserError::UserNotFound(id))?;
+        Self::ensure_modifiable(&existing)?;
 
 
         if let Some(ref new_username) = dto.username {
-            if new_username != &existing.username {
-                if let Some(_) = self.repository.find_by_username(new_username).await? {
-                    return Err(RepositoryError::ValidationError(format!(
-                        "Username '{}' is already taken",
-                        new_username
-                    )));
-                }
+            if new_username != &existing.username
+                && self.repository.find_by_username(new_username).await?.is_some()
+            {
+                return Err(UserError::UsernameTaken(new_username.clone()));
             }
         }
 
 
         if let Some(ref new_email) = dto.email {
-            if new_email != &existing.email {
-                if let Some(_) = self.repository.find_by_email(new_email).await? {
-                    return Err(RepositoryError::ValidationError(format!(
-                        "Email '{}' is already registered",
-                        new_email
-                    )));
-                }
+            if new_email != &existing.email
+                && self.repository.find_by_email(new_email).await?.is_some()
+            {
+                return Err(UserError::EmailTaken(new_email.clone()));
+            }
+        }
+
+        let email_changed = dto.email.as_ref().is_some_and(|email| email != &existing.email);
+        let existing_email = existing.email.clone();
+        let username = dto.username.clone().unwrap_or(existing.username);
+        let email = dto.email.clone().unwrap_or(existing.email);
+        let mut user = self
+            .repository
+            .update_user(id, dto)
+            .await
+            .map_err(|e| UserError::from_write(e, &username, &email))?;
+
+        if !email_changed {
+            return Ok(user);
+        }
+
+        // A new address has to be confirmed again.
+        if user.status == UserStatus::Active && self.notifier.is_some() {
+            user.status = UserStatus::PendingVerification;
+            user.email_verified_at = None;
+            user = self.repository.update(id, user).await?;
+        }
+        self.notify(
+            &existing_email,
+            Notification::EmailChanged {
+                username: user.username.clone(),
+                new_email: user.email.clone(),
+            },
+        )
+        .await;
+        self.send_verification(user).await
+    }
+
+    /// Check a username and password, returning the user they belong to.
+    /// Unknown users and wrong passwords fail alike with `InvalidCredentials`.
+    pub async fn authenticate(&self, username: &str, password: &str) -> UserResult<User> {
+        let user = self.repository.find_by_username(username).await?;
+        let stored = user.as_ref().and_then(|u| u.password_hash.as_deref());
+
+        match (verify_password(password, stored).await?, user) {
+            (true, Some(user)) if !user.status.can_sign_in() => {
+                Err(UserError::AccountInactive(user.status))
             }
+            (true, Some(user)) => Ok(user),
+            _ => Err(UserError::InvalidCredentials),
+        }
+    }
+
+    /// Fail unless user `id` exists and may still sign in; checked when a
+    /// session is refreshed.
+    pub async fn ensure_can_sign_in(&self, id: Uuid) -> UserResult<()> {
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| UserError::InvalidSession("Account no longer exists".to_string()))?;
+
+        if user.status.can_sign_in() {
+            Ok(())
+        } else {
+            Err(UserError::AccountInactive(user.status))
+        }
+    }
+
+    /// Users changing their own password must confirm the current one;
+    /// admins may reset any password without it.
+    pub async fn change_password(
+        &self,
+        caller: &Principal,
+        id: Uuid,
+        dto: ChangePasswordDto,
+    ) -> UserResult<()> {
+        UserPolicy::authorize(caller, UserAction::ChangePassword(id))?;
+
+        let user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound(id))?;
+        Self::ensure_modifiable(&user)?;
+
+        if caller.user_id == Some(id) {
+            let current = dto.current_password.as_deref().unwrap_or_default();
+            if !verify_password(current, user.password_hash.as_deref()).await? {
+                return Err(UserError::InvalidCredentials);
+            }
+        }
+
+        let password_hash = hash_password(&dto.new_password).await?;
+        Ok(self.repository.update_password_hash(id, password_hash).await?)
+    }
+
+    /// Start email verification of a pending account, returning the token to
+    /// deliver to its address. A new token replaces any earlier one.
+    pub async fn issue_verification_token(&self, caller: &Principal, id: Uuid) -> UserResult<String> {
+        UserPolicy::authorize(caller, UserAction::VerifyEmail(id))?;
+
+        let user = self.find_user(id).await?;
+        if user.status != UserStatus::PendingVerification {
+            return Err(UserError::InvalidStatusTransition {
+                from: user.status,
+                to: UserStatus::Active,
+            });
         }
 
-        self.repository.update_user(id, dto).await
+        let (_, token) = self.store_verification_token(user).await?;
+        Ok(token)
     }
 
-    pub async fn delete_user(&self, id: Uuid) -> RepositoryResult<bool> {
+    /// Mail a new verification token to a pending account, e.g. after the
+    /// first one expired.
+    pub async fn resend_verification(&self, caller: &Principal, id: Uuid) -> UserResult<()> {
+        UserPolicy::authorize(caller, UserAction::VerifyEmail(id))?;
+
+        let user = self.find_user(id).await?;
+        if user.status != UserStatus::PendingVerification {
+            return Err(UserError::InvalidStatusTransition {
+                from: user.status,
+                to: UserStatus::Active,
+            });
+        }
+
+        self.send_verification(user).await?;
+        Ok(())
+    }
+
+    /// Confirm the email address of a pending account with the token sent to it.
+    pub async fn verify_email(&self, caller: &Principal, id: Uuid, token: &str) -> UserResult<User> {
+        UserPolicy::authorize(caller, UserAction::VerifyEmail(id))?;
+
+        let mut user = self.find_user(id).await?;
+        if user.status != UserStatus::PendingVerification {
+            return Err(UserError::InvalidStatusTransition {
+                from: user.status,
+                to: UserStatus::Active,
+            });
+        }
+
+        let valid = match (&user.verification_token_hash, user.verification_expires_at) {
+            (Some(hash), Some(expires_at)) => *hash == hash_token(token) && !is_past(expires_at),
+            _ => false,
+        };
+        if !valid {
+            return Err(UserError::InvalidVerificationToken);
+        }
+
+        user.status = UserStatus::Active;
+        user.email_verified_at = Some(Utc::now());
+        user.verification_token_hash = None;
+        user.verification_expires_at = None;
+        self.set_status(user).await
+    }
+
+    /// Block an account. Suspended users cannot sign in or be updated.
+    pub async fn suspend_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        UserPolicy::authorize(caller, UserAction::Suspend(id))?;
+
+        let mut user = self.find_user(id).await?;
+        if !matches!(user.status, UserStatus::PendingVerification | UserStatus::Active) {
+            return Err(UserError::InvalidStatusTransition {
+                from: user.status,
+                to: UserStatus::Suspended,
+            });
+        }
+
+        user.status = UserStatus::Suspended;
+        let user = self.set_status(user).await?;
+        self.revoke_sessions(&user).await?;
+        Ok(user)
+    }
+
+    /// Lift a suspension or reopen a deactivated account. Accounts whose
+    /// address was never confirmed go back to pending verification, unless
+    /// there is no notifier to confirm it with.
+    pub async fn reactivate_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        UserPolicy::authorize(caller, UserAction::Reactivate(id))?;
+
+        let mut user = self.find_user(id).await?;
+        let next = if user.email_verified_at.is_some() || self.notifier.is_none() {
+            UserStatus::Active
+        } else {
+            UserStatus::PendingVerification
+        };
+        if !matches!(user.status, UserStatus::Suspended | UserStatus::Deactivated) {
+            return Err(UserError::InvalidStatusTransition { from: user.status, to: next });
+        }
+
+        user.status = next;
+        self.set_status(user).await
+    }
+
+    /// Close an account. Only an admin can reopen it.
+    pub async fn deactivate_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        UserPolicy::authorize(caller, UserAction::Deactivate(id))?;
+
+        let mut user = self.find_user(id).await?;
+        if user.status == UserStatus::Deactivated {
+            return Err(UserError::InvalidStatusTransition {
+                from: user.status,
+                to: UserStatus::Deactivated,
+            });
+        }
+
+        user.status = UserStatus::Deactivated;
+        let user = self.set_status(user).await?;
+        self.revoke_sessions(&user).await?;
+        Ok(user)
+    }
+
+    pub async fn delete_user(&self, caller: &Principal, id: Uuid) -> UserResult<bool> {
+        UserPolicy::authorize(caller, UserAction::Delete(id))?;
 
         let exists = self.repository.exists(id).await?;
         if !exists {
-            return Err(RepositoryError::NotFound(id));
+            return Err(UserError::UserNotFound(id));
         }
 
-        self.repository.delete(id).await
+        Ok(self.repository.delete(id).await?)
     }
 
-    pub async fn find_by_username(&self, username: &str) -> RepositoryResult<Option<User>> {
-        self.repository.find_by_username(username).await
+    pub async fn find_by_username(
+        &self,
+        caller: &Principal,
+        username: &str,
+    ) -> UserResult<Option<User>> {
+        UserPolicy::authorize(caller, UserAction::Search)?;
+        Ok(self.repository.find_by_username(username).await?)
     }
 
-    pub async fn find_by_email(&self, email: &str) -> RepositoryResult<Option<User>> {
-        self.repository.find_by_email(email).await
+    pub async fn find_by_email(&self, caller: &Principal, email: &str) -> UserResult<Option<User>> {
+        UserPolicy::authorize(caller, UserAction::Search)?;
+        Ok(self.repository.find_by_email(email).await?)
     }
 
     pub async fn get_users_by_age_range(
         &self,
+        caller: &Principal,
         min_age: i32,
         max_age: i32,
-    ) -> RepositoryResult<Vec<User>> {
+    ) -> UserResult<Vec<User>> {
+        UserPolicy::authorize(caller, UserAction::Search)?;
 
         if min_age > max_age {
-            return Err(RepositoryError::ValidationError(
-                "Minimum age cannot be greater than maximum age".to_string(),
-            ));
+            return Err(UserError::InvalidAgeRange {
+                min: min_age,
+                max: max_age,
+            });
         }
 
-        self.repository.find_by_age_range(min_age, max_age).await
+        Ok(self.repository.find_by_age_range(min_age, max_age).await?)
+    }
+
+    pub async fn get_user_count(&self, caller: &Principal) -> UserResult<usize> {
+        UserPolicy::authorize(caller, UserAction::ViewStatistics)?;
+        Ok(self.repository.count().await?)
     }
 
-    pub async fn get_user_count(&self) -> RepositoryResult<usize> {
-        self.repository.count().await
+    async fn store_verification_token(&self, mut user: User) -> UserResult<(User, String)> {
+        let token = generate_token("");
+        user.verification_token_hash = Some(hash_token(&token));
+        user.verification_expires_at = Some(add_days(Utc::now(), VERIFICATION_TOKEN_TTL_DAYS));
+        let user = self.repository.update(user.id, user).await?;
+        Ok((user, token))
     }
 
-    pub async fn get_statistics(&self) -> RepositoryResult<UserStatistics> {
+    /// Mail a fresh verification token to a pending account. Without a
+    /// notifier the account is activated instead, as it could never be verified.
+    async fn send_verification(&self, mut user: User) -> UserResult<User> {
+        if user.status != UserStatus::PendingVerification {
+            return Ok(user);
+        }
+        if self.notifier.is_none() {
+            user.status = UserStatus::Active;
+            return self.set_status(user).await;
+        }
+
+        let (user, token) = self.store_verification_token(user).await?;
+        self.notify(
+            &user.email,
+            Notification::VerifyEmail {
+                username: user.username.clone(),
+                user_id: user.id,
+                token,
+                valid_days: VERIFICATION_TOKEN_TTL_DAYS,
+            },
+        )
+        .await;
+        Ok(user)
+    }
+
+    /// Delivery failures are logged, not returned: the change that triggered
+    /// the notification has already been saved.
+    async fn notify(&self, to: &str, notification: Notification) {
+        let Some(notifier) = &self.notifier else {
+            return;
+        };
+        if let Err(e) = notifier.send(notification.to_message(to)).await {
+            tracing::warn!(to = %to, "Failed to send notification: {}", e);
+        }
+    }
+
+    async fn find_user(&self, id: Uuid) -> UserResult<User> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::UserNotFound(id))
+    }
+
+    async fn set_status(&self, mut user: User) -> UserResult<User> {
+        user.updated_at = Utc::now();
+        let user = self.repository.update(user.id, user).await?;
+
+        tracing::info!(user_id = %user.id, status = %user.status, "Account status changed");
+        Ok(user)
+    }
+
+    async fn revoke_sessions(&self, user: &User) -> UserResult<()> {
+        if let Some(sessions) = &self.sessions {
+            let revoked = sessions.revoke_all_for_user(user.id).await?;
+            tracing::info!(user_id = %user.id, status = %user.status, revoked, "Sessions revoked");
+        }
+        Ok(())
+    }
+
+    fn ensure_modifiable(user: &User) -> UserResult<()> {
+        match user.status {
+            UserStatus::Suspended | UserStatus::Deactivated => {
+                Err(UserError::AccountInactive(user.status))
+            }
+            UserStatus::PendingVerification | UserStatus::Active => Ok(()),
+        }
+    }
+
+    fn check_age(age: Option<i32>) -> UserResult<()> {
+        match age {
+            Some(age) if !(1..=150).contains(&age) => Err(UserError::InvalidAge(age)),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn get_statistics(&self, caller: &Principal) -> UserResult<UserStatistics> {
+        UserPolicy::authorize(caller, UserAction::ViewStatistics)?;
         let all_users = self.repository.find_all().await?;
         let total = all_users.len();
 
@@ -143,43 +480,357 @@ impl<R: UserRepository> UserService<R> {
 
 #[async_trait]
 impl<R: UserRepository + Send + Sync> IUserService for UserService<R> {
-    async fn create_user(&self, dto: CreateUserDto) -> RepositoryResult<User> {
-        self.create_user(dto).await
+    async fn create_user(&self, caller: &Principal, dto: CreateUserDto) -> UserResult<User> {
+        self.create_user(caller, dto).await
     }
     
-    async fn get_user(&self, id: Uuid) -> RepositoryResult<User> {
-        self.get_user(id).await
+    async fn get_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        self.get_user(caller, id).await
     }
     
-    async fn get_all_users(&self) -> RepositoryResult<Vec<User>> {
-        self.get_all_users().await
+    async fn get_all_users(&self, caller: &Principal) -> UserResult<Vec<User>> {
+        self.get_all_users(caller).await
     }
     
-    async fn update_user(&self, id: Uuid, dto: UpdateUserDto) -> RepositoryResult<User> {
-        self.update_user(id, dto).await
+    async fn update_user(&self, caller: &Principal, id: Uuid, dto: UpdateUserDto) -> UserResult<User> {
+        self.update_user(caller, id, dto).await
     }
     
-    async fn delete_user(&self, id: Uuid) -> RepositoryResult<bool> {
-        self.delete_user(id).await
+    async fn authenticate(&self, username: &str, password: &str) -> UserResult<User> {
+        self.authenticate(username, password).await
+    }
+
+    async fn change_password(&self, caller: &Principal, id: Uuid, dto: ChangePasswordDto) -> UserResult<()> {
+        self.change_password(caller, id, dto).await
+    }
+
+    async fn issue_verification_token(&self, caller: &Principal, id: Uuid) -> UserResult<String> {
+        self.issue_verification_token(caller, id).await
+    }
+
+    async fn resend_verification(&self, caller: &Principal, id: Uuid) -> UserResult<()> {
+        self.resend_verification(caller, id).await
+    }
+
+    async fn verify_email(&self, caller: &Principal, id: Uuid, token: &str) -> UserResult<User> {
+        self.verify_email(caller, id, token).await
+    }
+
+    async fn suspend_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        self.suspend_user(caller, id).await
+    }
+
+    async fn reactivate_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        self.reactivate_user(caller, id).await
+    }
+
+    async fn deactivate_user(&self, caller: &Principal, id: Uuid) -> UserResult<User> {
+        self.deactivate_user(caller, id).await
+    }
+
+    async fn delete_user(&self, caller: &Principal, id: Uuid) -> UserResult<bool> {
+        self.delete_user(caller, id).await
     }
     
-    async fn find_by_username(&self, username: &str) -> RepositoryResult<Option<User>> {
-        self.find_by_username(username).await
+    async fn find_by_username(&self, caller: &Principal, username: &str) -> UserResult<Option<User>> {
+        self.find_by_username(caller, username).await
     }
     
-    async fn find_by_email(&self, email: &str) -> RepositoryResult<Option<User>> {
-        self.find_by_email(email).await
+    async fn find_by_email(&self, caller: &Principal, email: &str) -> UserResult<Option<User>> {
+        self.find_by_email(caller, email).await
     }
     
-    async fn get_users_by_age_range(&self, min_age: i32, max_age: i32) -> RepositoryResult<Vec<User>> {
-        self.get_users_by_age_range(min_age, max_age).await
+    async fn get_users_by_age_range(&self, caller: &Principal, min_age: i32, max_age: i32) -> UserResult<Vec<User>> {
+        self.get_users_by_age_range(caller, min_age, max_age).await
     }
     
-    async fn get_user_count(&self) -> RepositoryResult<usize> {
-        self.get_user_count().await
+    async fn get_user_count(&self, caller: &Principal) -> UserResult<usize> {
+        self.get_user_count(caller).await
     }
     
-    async fn get_statistics(&self) -> RepositoryResult<UserStatistics> {
-        self.get_statistics().await
+    async fn get_statistics(&self, caller: &Principal) -> UserResult<UserStatistics> {
+        self.get_statistics(caller).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use baserepository::BaseRepository;
+    use core_auth::{JwtVerifier, TokenIssuer};
+    use core_config::AuthConfig;
+    use crate::notification::InMemoryNotifier;
+    use crate::repositories::{InMemorySessionRepository, InMemoryUserRepository};
+    use crate::service::SessionService;
+    use pkg::RepositoryError;
+
+    fn dto(username: &str, email: &str, age: Option<i32>) -> CreateUserDto {
+        CreateUserDto {
+            username: username.to_string(),
+            email: email.to_string(),
+            full_name: "Test User".to_string(),
+            age,
+            password: "Password123".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_domain_errors() {
+        let service = UserService::new(Arc::new(InMemoryUserRepository::new()));
+        let admin = Principal::system();
+        service.create_user(&admin, dto("jane", "jane@example.com", Some(30))).await.unwrap();
+
+        assert!(matches!(
+            service.create_user(&admin, dto("jane", "other@example.com", None)).await,
+            Err(UserError::UsernameTaken(name)) if name == "jane"
+        ));
+        assert!(matches!(
+            service.create_user(&admin, dto("john", "jane@example.com", None)).await,
+            Err(UserError::EmailTaken(_))
+        ));
+        assert!(matches!(
+            service.create_user(&admin, dto("john", "john@example.com", Some(200))).await,
+            Err(UserError::InvalidAge(200))
+        ));
+        assert!(matches!(
+            service.get_user(&admin, Uuid::new_v4()).await,
+            Err(UserError::UserNotFound(_))
+        ));
+        assert!(matches!(
+            service.get_users_by_age_range(&admin, 40, 20).await,
+            Err(UserError::InvalidAgeRange { min: 40, max: 20 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_policy_is_enforced() {
+        let service = UserService::new(Arc::new(InMemoryUserRepository::new()));
+        let admin = Principal::system();
+        let jane = service.create_user(&admin, dto("jane", "jane@example.com", None)).await.unwrap();
+        let john = service.create_user(&admin, dto("john", "john@example.com", None)).await.unwrap();
+
+        let caller = Principal {
+            subject: jane.id.to_string(),
+            user_id: Some(jane.id),
+            roles: vec!["user".to_string()],
+            scopes: Vec::new(),
+            expires_at: u64::MAX,
+        };
+        let rename = |name: &str| UpdateUserDto {
+            username: None,
+            email: None,
+            full_name: Some(name.to_string()),
+            age: None,
+        };
+
+        assert!(service.update_user(&caller, jane.id, rename("Jane Doe")).await.is_ok());
+        assert!(service.get_user(&caller, john.id).await.is_ok());
+
+        let forbidden = |result: UserResult<()>| {
+            matches!(result, Err(UserError::Repository(RepositoryError::Forbidden(_))))
+        };
+        assert!(forbidden(service.update_user(&caller, john.id, rename("Hacked")).await.map(|_| ())));
+        assert!(forbidden(service.delete_user(&caller, john.id).await.map(|_| ())));
+        assert!(forbidden(service.get_all_users(&caller).await.map(|_| ())));
+        assert!(forbidden(service.get_statistics(&caller).await.map(|_| ())));
+
+        assert_eq!(service.get_user(&admin, john.id).await.unwrap().full_name, "Test User");
+    }
+
+    #[tokio::test]
+    async fn test_passwords() {
+        let service = UserService::new(Arc::new(InMemoryUserRepository::new()));
+        let admin = Principal::system();
+        let jane = service.create_user(&admin, dto("jane", "jane@example.com", None)).await.unwrap();
+        assert!(jane.password_hash.as_deref().is_some_and(|h| h.starts_with("$argon2id$")));
+
+        assert_eq!(service.authenticate("jane", "Password123").await.unwrap().id, jane.id);
+        assert!(matches!(
+            service.authenticate("jane", "Password124").await,
+            Err(UserError::InvalidCredentials)
+        ));
+        assert!(matches!(
+            service.authenticate("nobody", "Password123").await,
+            Err(UserError::InvalidCredentials)
+        ));
+
+        let caller = Principal {
+            subject: jane.id.to_string(),
+            user_id: Some(jane.id),
+            roles: vec!["user".to_string()],
+            scopes: Vec::new(),
+            expires_at: u64::MAX,
+        };
+        let change = |current: Option<&str>| ChangePasswordDto {
+            current_password: current.map(str::to_string),
+            new_password: "NewPassword456".to_string(),
+        };
+
+        assert!(matches!(
+            service.change_password(&caller, jane.id, change(Some("wrong"))).await,
+            Err(UserError::InvalidCredentials)
+        ));
+        service.change_password(&caller, jane.id, change(Some("Password123"))).await.unwrap();
+        assert!(service.authenticate("jane", "NewPassword456").await.is_ok());
+        assert!(service.authenticate("jane", "Password123").await.is_err());
+
+        // Admins reset passwords without knowing the current one.
+        let reset = ChangePasswordDto {
+            current_password: None,
+            new_password: "Weak".to_string(),
+        };
+        assert!(matches!(
+            service.change_password(&admin, jane.id, reset).await,
+            Err(UserError::WeakPassword)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_status_lifecycle() {
+        let repository = Arc::new(InMemoryUserRepository::new());
+        let service = UserService::new(repository.clone())
+            .with_notifier(Arc::new(InMemoryNotifier::new()));
+        let admin = Principal::system();
+        let jane = service.create_user(&admin, dto("jane", "jane@example.com", None)).await.unwrap();
+        assert_eq!(jane.status, UserStatus::PendingVerification);
+
+        let token = service.issue_verification_token(&admin, jane.id).await.unwrap();
+        assert!(matches!(
+            service.verify_email(&admin, jane.id, "wrong").await,
+            Err(UserError::InvalidVerificationToken)
+        ));
+
+        // Expired tokens are rejected even when they match.
+        let mut stored = repository.find_by_id(jane.id).await.unwrap().unwrap();
+        let expires_at = stored.verification_expires_at.replace(add_days(Utc::now(), -1));
+        repository.update(jane.id, stored.clone()).await.unwrap();
+        assert!(matches!(
+            service.verify_email(&admin, jane.id, &token).await,
+            Err(UserError::InvalidVerificationToken)
+        ));
+        stored.verification_expires_at = expires_at;
+        repository.update(jane.id, stored).await.unwrap();
+
+        let verified = service.verify_email(&admin, jane.id, &token).await.unwrap();
+        assert_eq!(verified.status, UserStatus::Active);
+        assert!(verified.verification_token_hash.is_none());
+
+        service.suspend_user(&admin, jane.id).await.unwrap();
+        assert!(matches!(
+            service.authenticate("jane", "Password123").await,
+            Err(UserError::AccountInactive(UserStatus::Suspended))
+        ));
+        let rename = UpdateUserDto {
+            username: None,
+            email: None,
+            full_name: Some("Jane Doe".to_string()),
+            age: None,
+        };
+        assert!(matches!(
+            service.update_user(&admin, jane.id, rename).await,
+            Err(UserError::AccountInactive(_))
+        ));
+        assert!(matches!(
+            service.suspend_user(&admin, jane.id).await,
+            Err(UserError::InvalidStatusTransition { .. })
+        ));
+
+        let reactivated = service.reactivate_user(&admin, jane.id).await.unwrap();
+        assert_eq!(reactivated.status, UserStatus::Active);
+        assert!(service.authenticate("jane", "Password123").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_suspension_revokes_sessions() {
+        let config = AuthConfig {
+            jwt_secret: Some("test-secret-that-is-at-least-32-bytes".to_string()),
+            ..AuthConfig::default()
+        };
+        let session_repository = Arc::new(InMemorySessionRepository::new());
+        let sessions = SessionService::new(
+            session_repository.clone(),
+            Arc::new(TokenIssuer::from_config(&config).unwrap().unwrap()),
+            Arc::new(JwtVerifier::from_config(&config).unwrap()),
+            chrono::Duration::hours(24),
+        );
+        let service = UserService::new(Arc::new(InMemoryUserRepository::new()))
+            .with_sessions(session_repository);
+        let admin = Principal::system();
+        let jane = service.create_user(&admin, dto("jane", "jane@example.com", None)).await.unwrap();
+        let login = sessions.start(&jane).await.unwrap();
+
+        service.suspend_user(&admin, jane.id).await.unwrap();
+        assert!(matches!(
+            sessions.refresh(&login.refresh_token).await,
+            Err(UserError::InvalidSession(_))
+        ));
+
+        service.reactivate_user(&admin, jane.id).await.unwrap();
+        let login = sessions.start(&jane).await.unwrap();
+        service.deactivate_user(&admin, jane.id).await.unwrap();
+        assert!(matches!(
+            sessions.refresh(&login.refresh_token).await,
+            Err(UserError::InvalidSession(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_notifications() {
+        let notifier = Arc::new(InMemoryNotifier::new());
+        let service = UserService::new(Arc::new(InMemoryUserRepository::new()))
+            .with_notifier(notifier.clone());
+        let admin = Principal::system();
+
+        let jane = service.create_user(&admin, dto("jane", "jane@example.com", None)).await.unwrap();
+        let welcome = notifier.last_to("jane@example.com").unwrap();
+        let token = welcome.body.lines().find(|line| line.len() == 64).unwrap().to_string();
+        service.verify_email(&admin, jane.id, &token).await.unwrap();
+
+        let change_email = UpdateUserDto {
+            username: None,
+            email: Some("jane.doe@example.com".to_string()),
+            full_name: None,
+            age: None,
+        };
+        let jane = service.update_user(&admin, jane.id, change_email).await.unwrap();
+        assert_eq!(jane.status, UserStatus::PendingVerification);
+
+        let warning = notifier.last_to("jane@example.com").unwrap();
+        assert_eq!(warning.subject, "Your email address was changed");
+        assert!(warning.body.contains("jane.doe@example.com"));
+        assert!(notifier.last_to("jane.doe@example.com").unwrap().body.contains(&jane.id.to_string()));
+        assert_eq!(notifier.sent().len(), 3);
+
+        service.resend_verification(&admin, jane.id).await.unwrap();
+        assert_eq!(notifier.sent().len(), 4);
+        assert_eq!(notifier.last_to("jane.doe@example.com").unwrap().subject, welcome.subject);
+        assert!(welcome.body.contains(&format!("within {} days", VERIFICATION_TOKEN_TTL_DAYS)));
+    }
+
+    #[tokio::test]
+    async fn test_accounts_are_active_without_a_notifier() {
+        let service = UserService::new(Arc::new(InMemoryUserRepository::new()));
+        let admin = Principal::system();
+
+        let jane = service.create_user(&admin, dto("jane", "jane@example.com", None)).await.unwrap();
+        assert_eq!(jane.status, UserStatus::Active);
+        assert!(jane.verification_token_hash.is_none());
+
+        let change_email = UpdateUserDto {
+            username: None,
+            email: Some("jane.doe@example.com".to_string()),
+            full_name: None,
+            age: None,
+        };
+        let jane = service.update_user(&admin, jane.id, change_email).await.unwrap();
+        assert_eq!(jane.status, UserStatus::Active);
+
+        service.suspend_user(&admin, jane.id).await.unwrap();
+        let jane = service.reactivate_user(&admin, jane.id).await.unwrap();
+        assert_eq!(jane.status, UserStatus::Active);
+        assert!(matches!(
+            service.resend_verification(&admin, jane.id).await,
+            Err(UserError::InvalidStatusTransition { .. })
+        ));
     }
 }
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use core_auth::{JwtVerifier, Principal, TokenIssuer, TokenPair, USER_ROLE};
+use crate::constants::{UserError, UserResult};
+use crate::domain::{Session, User};
+use crate::repositories::SessionRepository;
+use super::policy::{UserAction, UserPolicy};
+
+/// Login sessions behind refresh tokens.
+///
+/// Refresh tokens are single use: each refresh issues a new pair and records
+/// the new token's `jti` on the session. Presenting a refresh token that was
+/// already rotated away means it leaked, so the whole session is revoked.
+/// Access tokens stay valid until they expire, which is why they are short-lived.
+pub struct SessionService<S: SessionRepository> {
+    sessions: Arc<S>,
+    issuer: Arc<TokenIssuer>,
+    verifier: Arc<JwtVerifier>,
+    timeout: Duration,
+}
+
+impl<S: SessionRepository> SessionService<S> {
+    pub fn new(
+        sessions: Arc<S>,
+        issuer: Arc<TokenIssuer>,
+        verifier: Arc<JwtVerifier>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            sessions,
+            issuer,
+            verifier,
+            timeout,
+        }
+    }
+
+    /// Open a session for a user who just authenticated.
+    pub async fn start(&self, user: &User) -> UserResult<TokenPair> {
+        let mut session = Session::new(user.id, String::new(), self.timeout);
+        let tokens = self.issuer.issue(
+            &user.id.to_string(),
+            &[USER_ROLE.to_string()],
+            &session.id.to_string(),
+        )?;
+
+        session.refresh_token_id = tokens.refresh_token_id.clone();
+        self.sessions.save(session).await?;
+        Ok(tokens)
+    }
+
+    /// Exchange a refresh token for a new token pair.
+    pub async fn refresh(&self, refresh_token: &str) -> UserResult<TokenPair> {
+        let claims = self.verifier.verify_refresh(refresh_token)?;
+        let (session, token_id) = self.session_of(&claims).await?;
+
+        if !session.is_active(Utc::now()) {
+            return Err(UserError::InvalidSession("Session has expired or was revoked".to_string()));
+        }
+        if session.refresh_token_id != token_id {
+            return Err(self.reuse_detected(&session).await);
+        }
+
+        let tokens = self.issuer.issue(&claims.sub, &claims.roles, &session.id.to_string())?;
+        if !self
+            .sessions
+            .rotate(session.id, &token_id, &tokens.refresh_token_id)
+            .await?
+        {
+            // A concurrent refresh with the same token won the race.
+            return Err(self.reuse_detected(&session).await);
+        }
+
+        Ok(tokens)
+    }
+
+    /// The user a refresh token was issued to.
+    pub async fn user_of(&self, refresh_token: &str) -> UserResult<Uuid> {
+        let claims = self.verifier.verify_refresh(refresh_token)?;
+        let (session, _) = self.session_of(&claims).await?;
+        Ok(session.user_id)
+    }
+
+    /// End the session a refresh token belongs to. Logging out twice is not an error.
+    pub async fn logout(&self, refresh_token: &str) -> UserResult<()> {
+        let claims = self.verifier.verify_refresh(refresh_token)?;
+        let (session, _) = self.session_of(&claims).await?;
+
+        if self.sessions.revoke(session.id).await? {
+            tracing::info!(session_id = %session.id, user_id = %session.user_id, "Session ended by logout");
+        }
+        Ok(())
+    }
+
+    /// Revoke every session of `user_id` (admin only). Returns how many were active.
+    pub async fn revoke_all(&self, caller: &Principal, user_id: Uuid) -> UserResult<usize> {
+        UserPolicy::authorize(caller, UserAction::RevokeSessions(user_id))?;
+
+        let revoked = self.sessions.revoke_all_for_user(user_id).await?;
+        tracing::info!(%user_id, revoked, by = %caller.subject, "Revoked all sessions of user");
+        Ok(revoked)
+    }
+
+    async fn session_of(&self, claims: &core_auth::Claims) -> UserResult<(Session, String)> {
+        let invalid = || UserError::InvalidSession("Invalid refresh token".to_string());
+
+        let session_id = claims
+            .sid
+            .as_deref()
+            .and_then(|sid| Uuid::parse_str(sid).ok())
+            .ok_or_else(invalid)?;
+        let token_id = claims.jti.clone().ok_or_else(invalid)?;
+        let session = self
+            .sessions
+            .find_by_id(session_id)
+            .await?
+            .ok_or_else(invalid)?;
+
+        Ok((session, token_id))
+    }
+
+    async fn reuse_detected(&self, session: &Session) -> UserError {
+        tracing::warn!(
+            session_id = %session.id,
+            user_id = %session.user_id,
+            "Refresh token reuse detected, revoking session"
+        );
+        if let Err(e) = self.sessions.revoke(session.id).await {
+            return e.into();
+        }
+        UserError::InvalidSession("Refresh token was already used; the session has been revoked".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_config::AuthConfig;
+    use baserepository::BaseRepository;
+    use crate::repositories::InMemorySessionRepository;
+
+    fn service() -> (SessionService<InMemorySessionRepository>, Arc<InMemorySessionRepository>) {
+        let config = AuthConfig {
+            jwt_secret: Some("test-secret-that-is-at-least-32-bytes".to_string()),
+            ..AuthConfig::default()
+        };
+        let sessions = Arc::new(InMemorySessionRepository::new());
+        let service = SessionService::new(
+            sessions.clone(),
+            Arc::new(TokenIssuer::from_config(&config).unwrap().unwrap()),
+            Arc::new(JwtVerifier::from_config(&config).unwrap()),
+            Duration::hours(24),
+        );
+        (service, sessions)
+    }
+
+    fn user() -> User {
+        User::new("jane".into(), "jane@example.com".into(), "Jane Doe".into(), None)
+    }
+
+    fn invalid_session<T>(result: UserResult<T>) -> bool {
+        matches!(result, Err(UserError::InvalidSession(_)))
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_and_detects_reuse() {
+        let (service, sessions) = service();
+        let login = service.start(&user()).await.unwrap();
+
+        let refreshed = service.refresh(&login.refresh_token).await.unwrap();
+        assert_ne!(refreshed.refresh_token, login.refresh_token);
+
+        // Replaying the first refresh token revokes the session, so even the
+        // newest token stops working.
+        assert!(invalid_session(service.refresh(&login.refresh_token).await));
+        assert!(invalid_session(service.refresh(&refreshed.refresh_token).await));
+
+        let session = &sessions.find_all().await.unwrap()[0];
+        assert!(session.revoked_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_logout_and_revoke_all() {
+        let (service, _) = service();
+        let jane = user();
+
+        let first = service.start(&jane).await.unwrap();
+        service.logout(&first.refresh_token).await.unwrap();
+        service.logout(&first.refresh_token).await.unwrap();
+        assert!(invalid_session(service.refresh(&first.refresh_token).await));
+
+        let second = service.start(&jane).await.unwrap();
+        let third = service.start(&jane).await.unwrap();
+
+        let as_jane = Principal {
+            subject: jane.id.to_string(),
+            user_id: Some(jane.id),
+            roles: vec![USER_ROLE.to_string()],
+            scopes: Vec::new(),
+            expires_at: u64::MAX,
+        };
+        assert!(service.revoke_all(&as_jane, jane.id).await.is_err());
+        assert_eq!(service.revoke_all(&Principal::system(), jane.id).await.unwrap(), 2);
+
+        assert!(invalid_session(service.refresh(&second.refresh_token).await));
+        assert!(invalid_session(service.refresh(&third.refresh_token).await));
+    }
+
+    #[tokio::test]
+    async fn test_expired_sessions_cannot_refresh() {
+        let (mut service, _) = service();
+        service.timeout = Duration::seconds(-1);
+
+        let login = service.start(&user()).await.unwrap();
+        assert!(invalid_session(service.refresh(&login.refresh_token).await));
+    }
+}
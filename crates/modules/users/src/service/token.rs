@@ -0,0 +1,37 @@
+use std::fmt::Write;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// A random 256-bit secret, hex encoded after `prefix`.
+///
+/// Secrets this long cannot be guessed, so storing their SHA-256 is enough;
+/// unlike passwords they need no slow hash.
+pub(crate) fn generate_token(prefix: &str) -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().fold(prefix.to_string(), |mut token, byte| {
+        let _ = write!(token, "{:02x}", byte);
+        token
+    })
+}
+
+/// Hex SHA-256 of a token, the only form in which tokens are stored.
+pub(crate) fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_unique_and_hash_stably() {
+        let token = generate_token("uk_");
+        assert!(token.starts_with("uk_"));
+        assert_eq!(token.len(), 3 + 64);
+        assert_ne!(token, generate_token("uk_"));
+
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_eq!(hash_token(&token).len(), 64);
+    }
+}
@@ -0,0 +1,184 @@
+//! Mapping of driver errors onto [`RepositoryError`] variants, keeping the
+//! original error as the source.
+
+use crate::errors::{BoxError, RepositoryError};
+
+impl RepositoryError {
+    /// Classify a Postgres error by its SQLSTATE code.
+    pub fn from_sqlstate(code: &str, constraint: Option<String>, source: BoxError) -> Self {
+        match code {
+            "23505" => RepositoryError::UniqueViolation { constraint, source },
+            "23503" => RepositoryError::ForeignKeyViolation { constraint, source },
+            "23514" => RepositoryError::CheckViolation { constraint, source },
+            // query_canceled (statement_timeout), lock_not_available
+            "57014" | "55P03" => RepositoryError::Timeout { source },
+            // admin/crash shutdown, cannot_connect_now, too_many_connections
+            "57P01" | "57P02" | "57P03" | "53300" => RepositoryError::Unavailable { source },
+            // connection_exception class
+            code if code.starts_with("08") => RepositoryError::Unavailable { source },
+            _ => RepositoryError::Database { source },
+        }
+    }
+
+    /// Classify a MongoDB server error by its numeric code. `message` is the
+    /// server's error message, used to recover the index of duplicate keys.
+    pub fn from_mongo_code(code: i32, message: &str, source: BoxError) -> Self {
+        match code {
+            // DuplicateKey and its legacy variants
+            11000 | 11001 | 12582 => RepositoryError::UniqueViolation {
+                constraint: duplicate_key_index(message),
+                source,
+            },
+            // DocumentValidationFailure
+            121 => RepositoryError::CheckViolation {
+                constraint: None,
+                source,
+            },
+            // ExceededTimeLimit, MaxTimeMSExpired, LockTimeout, NetworkTimeout
+            262 | 50 | 24 | 89 => RepositoryError::Timeout { source },
+            // HostUnreachable, HostNotFound, ShutdownInProgress, NotWritablePrimary,
+            // InterruptedDueToReplStateChange, NotPrimaryNoSecondaryOk, PrimarySteppedDown
+            6 | 7 | 91 | 10107 | 11602 | 13435 | 189 => RepositoryError::Unavailable { source },
+            _ => RepositoryError::Database { source },
+        }
+    }
+}
+
+/// Index name from `E11000 duplicate key error collection: db.users index: username_1 dup key: ...`.
+fn duplicate_key_index(message: &str) -> Option<String> {
+    let rest = message.split("index: ").nth(1)?;
+    rest.split_whitespace().next().map(str::to_string)
+}
+
+#[cfg(feature = "sqlx")]
+impl From<sqlx::Error> for RepositoryError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::Database(db) => match db.code() {
+                Some(code) => {
+                    let code = code.into_owned();
+                    let constraint = db.constraint().map(str::to_string);
+                    Self::from_sqlstate(&code, constraint, Box::new(error))
+                }
+                None => Self::database(error),
+            },
+            sqlx::Error::PoolTimedOut => RepositoryError::Timeout {
+                source: Box::new(error),
+            },
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => RepositoryError::Unavailable {
+                source: Box::new(error),
+            },
+            _ => Self::database(error),
+        }
+    }
+}
+
+#[cfg(feature = "mongodb")]
+impl From<mongodb::error::Error> for RepositoryError {
+    fn from(error: mongodb::error::Error) -> Self {
+        use mongodb::error::{ErrorKind, WriteFailure};
+
+        let server_error = match error.kind.as_ref() {
+            ErrorKind::Write(WriteFailure::WriteError(e)) => Some((e.code, e.message.clone())),
+            ErrorKind::Command(e) => Some((e.code, e.message.clone())),
+            ErrorKind::BulkWrite(failure) => failure
+                .write_errors
+                .as_ref()
+                .and_then(|errors| errors.first())
+                .map(|e| (e.code, e.message.clone())),
+            _ => None,
+        };
+
+        if let Some((code, message)) = server_error {
+            return Self::from_mongo_code(code, &message, Box::new(error));
+        }
+
+        match error.kind.as_ref() {
+            ErrorKind::Io(_)
+            | ErrorKind::ServerSelection { .. }
+            | ErrorKind::ConnectionPoolCleared { .. }
+            | ErrorKind::DnsResolve { .. } => RepositoryError::Unavailable {
+                source: Box::new(error),
+            },
+            _ => Self::database(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    fn source(message: &str) -> BoxError {
+        message.to_string().into()
+    }
+
+    #[test]
+    fn test_sqlstate_classification() {
+        let error = RepositoryError::from_sqlstate(
+            "23505",
+            Some("users_username_key".to_string()),
+            source("duplicate key value violates unique constraint"),
+        );
+        assert!(matches!(error, RepositoryError::UniqueViolation { .. }));
+        assert_eq!(error.constraint(), Some("users_username_key"));
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "duplicate key value violates unique constraint"
+        );
+
+        assert!(matches!(
+            RepositoryError::from_sqlstate("23503", None, source("fk")),
+            RepositoryError::ForeignKeyViolation { .. }
+        ));
+        // A NULL in a NOT NULL column is a bug in the code, not bad input.
+        assert!(RepositoryError::from_sqlstate("23502", None, source("null")).is_internal());
+        assert!(RepositoryError::from_sqlstate("57014", None, source("cancel")).is_retryable());
+        assert!(RepositoryError::from_sqlstate("08006", None, source("lost")).is_retryable());
+        assert!(matches!(
+            RepositoryError::from_sqlstate("42P01", None, source("no table")),
+            RepositoryError::Database { .. }
+        ));
+    }
+
+    #[test]
+    fn test_mongo_code_classification() {
+        let error = RepositoryError::from_mongo_code(
+            11000,
+            "E11000 duplicate key error collection: app.users index: email_1 dup key: { email: \"a@b.c\" }",
+            source("dup"),
+        );
+        assert!(matches!(error, RepositoryError::UniqueViolation { .. }));
+        assert_eq!(error.constraint(), Some("email_1"));
+
+        assert!(matches!(
+            RepositoryError::from_mongo_code(50, "", source("slow")),
+            RepositoryError::Timeout { .. }
+        ));
+        assert!(matches!(
+            RepositoryError::from_mongo_code(91, "", source("shutdown")),
+            RepositoryError::Unavailable { .. }
+        ));
+    }
+
+    #[cfg(feature = "sqlx")]
+    #[test]
+    fn test_from_sqlx_error() {
+        assert!(matches!(
+            RepositoryError::from(sqlx::Error::PoolTimedOut),
+            RepositoryError::Timeout { .. }
+        ));
+        assert!(matches!(
+            RepositoryError::from(sqlx::Error::PoolClosed),
+            RepositoryError::Unavailable { .. }
+        ));
+        assert!(matches!(
+            RepositoryError::from(sqlx::Error::RowNotFound),
+            RepositoryError::Database { .. }
+        ));
+    }
+}
@@ -1,6 +1,110 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
+/// Stable, machine-readable error codes returned to API clients in the
+/// `code` member of problem responses. Never rename a variant: clients
+/// match on the serialized value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    AlreadyExists,
+    ValidationFailed,
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    Conflict,
+    DatabaseError,
+    InternalError,
+    ServiceUnavailable,
+    Timeout,
+    RateLimited,
+}
+
+/// A code heading a problem response. [`ErrorCode`] covers errors any service
+/// can return; modules implement this for the codes of their own domain.
+pub trait ProblemCode {
+    /// Serialized value of the `code` member, e.g. `NOT_FOUND`.
+    fn as_str(&self) -> &'static str;
+
+    /// HTTP status code this error is reported with.
+    fn status(&self) -> u16;
+
+    /// Short, human-readable summary that does not vary between occurrences.
+    fn title(&self) -> &'static str;
+
+    /// Problem type URI, e.g. `urn:problem-type:not-found`.
+    fn type_uri(&self) -> String {
+        format!(
+            "urn:problem-type:{}",
+            self.as_str().to_lowercase().replace('_', "-")
+        )
+    }
+}
+
+impl ProblemCode for ErrorCode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::AlreadyExists => "ALREADY_EXISTS",
+            ErrorCode::ValidationFailed => "VALIDATION_FAILED",
+            ErrorCode::BadRequest => "BAD_REQUEST",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::DatabaseError => "DATABASE_ERROR",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+        }
+    }
+
+    fn status(&self) -> u16 {
+        match self {
+            ErrorCode::NotFound => 404,
+            ErrorCode::AlreadyExists | ErrorCode::Conflict => 409,
+            ErrorCode::BadRequest => 400,
+            // The request was well-formed but its content was rejected.
+            ErrorCode::ValidationFailed => 422,
+            ErrorCode::Unauthorized => 401,
+            ErrorCode::Forbidden => 403,
+            ErrorCode::RateLimited => 429,
+            ErrorCode::DatabaseError | ErrorCode::InternalError => 500,
+            ErrorCode::ServiceUnavailable => 503,
+            ErrorCode::Timeout => 504,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            ErrorCode::NotFound => "Resource not found",
+            ErrorCode::AlreadyExists => "Resource already exists",
+            ErrorCode::ValidationFailed => "Validation failed",
+            ErrorCode::BadRequest => "Bad request",
+            ErrorCode::Unauthorized => "Unauthorized",
+            ErrorCode::Forbidden => "Forbidden",
+            ErrorCode::Conflict => "Conflict",
+            ErrorCode::DatabaseError | ErrorCode::InternalError => "Internal server error",
+            ErrorCode::ServiceUnavailable => "Service unavailable",
+            ErrorCode::Timeout => "Operation timed out",
+            ErrorCode::RateLimited => "Too many requests",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Underlying driver error kept as the `source` of a [`RepositoryError`].
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
 #[derive(Error, Debug)]
 pub enum RepositoryError {
     #[error("Entity with ID {0} not found")]
@@ -26,10 +130,103 @@ pub enum RepositoryError {
 
     #[error("Bad request: {0}")]
     BadRequest(String),
+
+    #[error("Unique constraint violated{}", constraint_suffix(.constraint))]
+    UniqueViolation {
+        constraint: Option<String>,
+        source: BoxError,
+    },
+
+    #[error("Foreign key constraint violated{}", constraint_suffix(.constraint))]
+    ForeignKeyViolation {
+        constraint: Option<String>,
+        source: BoxError,
+    },
+
+    #[error("Check constraint violated{}", constraint_suffix(.constraint))]
+    CheckViolation {
+        constraint: Option<String>,
+        source: BoxError,
+    },
+
+    #[error("Database operation timed out: {source}")]
+    Timeout { source: BoxError },
+
+    #[error("Database unavailable: {source}")]
+    Unavailable { source: BoxError },
+
+    #[error("Database error: {source}")]
+    Database { source: BoxError },
+}
+
+fn constraint_suffix(constraint: &Option<String>) -> String {
+    match constraint {
+        Some(name) => format!(" ({})", name),
+        None => String::new(),
+    }
 }
 
 pub type RepositoryResult<T> = Result<T, RepositoryError>;
 
+impl RepositoryError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RepositoryError::NotFound(_) => ErrorCode::NotFound,
+            RepositoryError::AlreadyExists(_) => ErrorCode::AlreadyExists,
+            RepositoryError::ValidationError(_) => ErrorCode::ValidationFailed,
+            RepositoryError::DatabaseError(_) => ErrorCode::DatabaseError,
+            RepositoryError::InternalError(_) => ErrorCode::InternalError,
+            RepositoryError::Unauthorized(_) => ErrorCode::Unauthorized,
+            RepositoryError::Forbidden(_) => ErrorCode::Forbidden,
+            RepositoryError::BadRequest(_) => ErrorCode::BadRequest,
+            RepositoryError::UniqueViolation { .. }
+            | RepositoryError::ForeignKeyViolation { .. } => ErrorCode::Conflict,
+            RepositoryError::CheckViolation { .. } => ErrorCode::ValidationFailed,
+            RepositoryError::Timeout { .. } => ErrorCode::Timeout,
+            RepositoryError::Unavailable { .. } => ErrorCode::ServiceUnavailable,
+            RepositoryError::Database { .. } => ErrorCode::DatabaseError,
+        }
+    }
+
+    /// Wrap a driver error that has no more specific classification.
+    pub fn database(source: impl Into<BoxError>) -> Self {
+        RepositoryError::Database {
+            source: source.into(),
+        }
+    }
+
+    /// Name of the violated constraint (or Mongo index), if known.
+    pub fn constraint(&self) -> Option<&str> {
+        match self {
+            RepositoryError::UniqueViolation { constraint, .. }
+            | RepositoryError::ForeignKeyViolation { constraint, .. }
+            | RepositoryError::CheckViolation { constraint, .. } => constraint.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Transient failures worth retrying: timeouts and lost connections.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            RepositoryError::Timeout { .. } | RepositoryError::Unavailable { .. }
+        )
+    }
+
+    /// Whether the message may contain implementation details (SQL, hosts,
+    /// driver errors) that must not be shown to clients.
+    pub fn is_internal(&self) -> bool {
+        matches!(
+            self,
+            RepositoryError::DatabaseError(_)
+                | RepositoryError::InternalError(_)
+                | RepositoryError::Timeout { .. }
+                | RepositoryError::Unavailable { .. }
+                | RepositoryError::Database { .. }
+        )
+    }
+}
+
 impl From<String> for RepositoryError {
     fn from(s: String) -> Self {
         RepositoryError::ValidationError(s)
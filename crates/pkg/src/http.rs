@@ -0,0 +1,137 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::errors::ErrorCode;
+use crate::problem::{ProblemDetails, PROBLEM_JSON};
+
+/// Header carrying the request ID, accepted from clients and echoed back.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request ID that is reused as-is.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ID of the request being handled, if [`request_id`] wraps the handler.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Middleware assigning each request an ID: the incoming `X-Request-Id` if it
+/// is reasonable, otherwise a new UUID. The ID is returned in the response
+/// header and included in problem responses and logs.
+///
+/// ```ignore
+/// let app = router.layer(axum::middleware::from_fn(pkg::http::request_id));
+/// ```
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        request.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    let span = tracing::info_span!("request", request_id = %id);
+    let mut response = REQUEST_ID
+        .scope(id.clone(), next.run(request).instrument(span))
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+impl ProblemDetails {
+    /// Problem for a rejected extractor (malformed JSON body, path segment or
+    /// query string), keeping the rejection's status and message.
+    pub fn from_rejection(status: StatusCode, detail: impl Into<String>) -> Self {
+        let code = if status == StatusCode::UNPROCESSABLE_ENTITY {
+            ErrorCode::ValidationFailed
+        } else {
+            ErrorCode::BadRequest
+        };
+        let mut problem = Self::new(code).with_detail(detail);
+        problem.status = status.as_u16();
+        problem
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(mut self) -> Response {
+        if self.request_id.is_none() {
+            self.request_id = current_request_id();
+        }
+
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(self)).into_response();
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorCode;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn failing() -> ProblemDetails {
+        ProblemDetails::new(ErrorCode::NotFound)
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/", get(failing))
+            .layer(middleware::from_fn(request_id))
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_problem_response_carries_request_id() {
+        let request = Request::get("/")
+            .header("x-request-id", "abc-123")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], PROBLEM_JSON);
+        assert_eq!(response.headers()["x-request-id"], "abc-123");
+        assert_eq!(body_json(response).await["request_id"], "abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_generated_when_missing() {
+        let response = app()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let header = response.headers()["x-request-id"].to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&header).is_ok());
+        assert_eq!(body_json(response).await["request_id"], header);
+    }
+}
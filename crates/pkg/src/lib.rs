@@ -1,9 +1,14 @@
+pub mod classify;
 pub mod errors;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod problem;
 pub mod types;
 pub mod logging;
 pub mod utils;
 
 // Re-exports for convenience
 pub use errors::*;
+pub use problem::*;
 pub use types::*;
 pub use logging::*;
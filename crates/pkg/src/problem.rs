@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::errors::{ErrorCode, ProblemCode, RepositoryError};
+
+/// Media type of [`ProblemDetails`] responses (RFC 7807).
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Detail sent instead of the real message for internal errors.
+pub const INTERNAL_ERROR_DETAIL: &str = "An unexpected error occurred";
+
+/// An RFC 7807 problem document, extended with a stable `code`, the
+/// `request_id` of the failed request and per-field validation `errors`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// [`ErrorCode`] or a module's own [`ProblemCode`], as serialized.
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// One failed constraint on one input field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Path to the field, e.g. `email` or `addresses[0].city`.
+    pub field: String,
+    /// Validator that failed, e.g. `length`, `email`, `range`.
+    pub code: String,
+    pub message: String,
+}
+
+impl ProblemDetails {
+    pub fn new(code: impl ProblemCode) -> Self {
+        Self {
+            type_uri: code.type_uri(),
+            title: code.title().to_string(),
+            status: code.status(),
+            detail: None,
+            instance: None,
+            code: code.as_str().to_string(),
+            request_id: None,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    pub fn with_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    /// `VALIDATION_FAILED` with one entry per failed field constraint.
+    pub fn from_validation_errors(errors: &ValidationErrors) -> Self {
+        let field_errors = FieldError::from_validation_errors(errors);
+        Self::new(ErrorCode::ValidationFailed)
+            .with_detail(format!("{} field(s) failed validation", field_errors.len()))
+            .with_errors(field_errors)
+    }
+
+    /// Map a repository error using its [`ErrorCode`]. Internal errors are
+    /// logged here and replaced by a generic detail in the response.
+    pub fn from_repository_error(error: &RepositoryError) -> Self {
+        Self::with_code(error.code(), error)
+    }
+
+    /// Like [`ProblemDetails::from_repository_error`] with a more specific code.
+    pub fn with_code(code: impl ProblemCode, error: &RepositoryError) -> Self {
+        if error.is_internal() {
+            tracing::error!(code = code.as_str(), "Request failed: {}", error);
+            return Self::new(code).with_detail(INTERNAL_ERROR_DETAIL);
+        }
+
+        let detail = match error {
+            RepositoryError::ValidationError(msg)
+            | RepositoryError::Unauthorized(msg)
+            | RepositoryError::Forbidden(msg)
+            | RepositoryError::BadRequest(msg) => msg.clone(),
+            // Constraint names are part of the schema, not for clients.
+            RepositoryError::UniqueViolation { .. } => "Unique constraint violated".to_string(),
+            RepositoryError::ForeignKeyViolation { .. } => {
+                "Foreign key constraint violated".to_string()
+            }
+            RepositoryError::CheckViolation { .. } => "Check constraint violated".to_string(),
+            other => other.to_string(),
+        };
+        Self::new(code).with_detail(detail)
+    }
+}
+
+impl FieldError {
+    /// Flatten nested validation errors into field paths, sorted by field.
+    pub fn from_validation_errors(errors: &ValidationErrors) -> Vec<Self> {
+        let mut fields = Vec::new();
+        collect_field_errors("", errors, &mut fields);
+        fields.sort_by(|a, b| a.field.cmp(&b.field).then_with(|| a.code.cmp(&b.code)));
+        fields
+    }
+
+    fn from_validation_error(field: String, error: &ValidationError) -> Self {
+        let message = match &error.message {
+            Some(message) => message.to_string(),
+            None => default_message(&error.code, &error.params),
+        };
+
+        Self {
+            field,
+            code: error.code.to_string(),
+            message,
+        }
+    }
+}
+
+fn collect_field_errors(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => out.extend(
+                errors
+                    .iter()
+                    .map(|error| FieldError::from_validation_error(path.clone(), error)),
+            ),
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(&path, nested, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(&format!("{}[{}]", path, index), nested, out);
+                }
+            }
+        }
+    }
+}
+
+fn default_message(code: &str, params: &HashMap<Cow<'static, str>, serde_json::Value>) -> String {
+    let min = params.get("min");
+    let max = params.get("max");
+
+    match (code, min, max) {
+        ("length", Some(min), Some(max)) => format!("must be {} to {} characters long", min, max),
+        ("length", Some(min), None) => format!("must be at least {} characters long", min),
+        ("length", None, Some(max)) => format!("must be at most {} characters long", max),
+        ("range", Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+        ("range", Some(min), None) => format!("must be at least {}", min),
+        ("range", None, Some(max)) => format!("must be at most {}", max),
+        ("email", _, _) => "must be a valid email address".to_string(),
+        ("url", _, _) => "must be a valid URL".to_string(),
+        ("required", _, _) => "is required".to_string(),
+        (code, _, _) => format!("is invalid ({})", code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Signup {
+        #[validate(length(min = 3, max = 50))]
+        username: String,
+        #[validate(email)]
+        email: String,
+        #[validate(range(min = 1, max = 150, message = "age is out of range"))]
+        age: i32,
+    }
+
+    #[test]
+    fn test_validation_errors_become_field_errors() {
+        let signup = Signup {
+            username: "ab".to_string(),
+            email: "not-an-email".to_string(),
+            age: 200,
+        };
+
+        let problem = ProblemDetails::from_validation_errors(&signup.validate().unwrap_err());
+
+        assert_eq!(problem.status, 422);
+        assert_eq!(problem.code, "VALIDATION_FAILED");
+        assert_eq!(
+            problem.errors,
+            vec![
+                FieldError {
+                    field: "age".to_string(),
+                    code: "range".to_string(),
+                    message: "age is out of range".to_string(),
+                },
+                FieldError {
+                    field: "email".to_string(),
+                    code: "email".to_string(),
+                    message: "must be a valid email address".to_string(),
+                },
+                FieldError {
+                    field: "username".to_string(),
+                    code: "length".to_string(),
+                    message: "must be 3 to 50 characters long".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_internal_errors_are_redacted() {
+        let error = RepositoryError::DatabaseError(
+            "relation \"users\" does not exist at db.internal:5432".to_string(),
+        );
+
+        let problem = ProblemDetails::from_repository_error(&error);
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.code, "DATABASE_ERROR");
+        assert_eq!(problem.detail.as_deref(), Some(INTERNAL_ERROR_DETAIL));
+    }
+
+    #[test]
+    fn test_constraint_names_are_not_shown() {
+        let error = RepositoryError::UniqueViolation {
+            constraint: Some("users_username_key".to_string()),
+            source: "duplicate key value violates unique constraint".into(),
+        };
+
+        let problem = ProblemDetails::from_repository_error(&error);
+        assert_eq!(problem.status, 409);
+        assert_eq!(problem.detail.as_deref(), Some("Unique constraint violated"));
+    }
+
+    #[test]
+    fn test_problem_serializes_per_rfc_7807() {
+        let id = Uuid::nil();
+        let problem = ProblemDetails::from_repository_error(&RepositoryError::NotFound(id))
+            .with_instance("/api/users/00000000-0000-0000-0000-000000000000")
+            .with_request_id("req-1");
+
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["type"], "urn:problem-type:not-found");
+        assert_eq!(json["title"], "Resource not found");
+        assert_eq!(json["status"], 404);
+        assert_eq!(json["code"], "NOT_FOUND");
+        assert_eq!(json["request_id"], "req-1");
+        assert!(json.get("errors").is_none());
+    }
+}
@@ -13,7 +13,7 @@ pub fn is_valid_email(email: &str) -> bool {
 /// Validate username (alphanumeric and underscores, 3-20 chars)
 pub fn is_valid_username(username: &str) -> bool {
     let len = username.len();
-    len >= 3 && len <= 20 && username.chars().all(|c| c.is_alphanumeric() || c == '_')
+    (3..=20).contains(&len) && username.chars().all(|c| c.is_alphanumeric() || c == '_')
 }
 
 /// Validate password strength (at least 8 chars, with uppercase, lowercase, and digit)